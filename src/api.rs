@@ -1,30 +1,163 @@
 //! [`api`](self).
 
-use bitcoin::{BlockHash, ScriptBuf, TxMerkleNode, Txid};
-use serde::Deserialize;
+use core::fmt;
+use std::collections::HashMap;
+
+use bitcoin::address::NetworkUnchecked;
+use bitcoin::hashes::{Hash, HashEngine, sha256d};
+use bitcoin::hex::{self, FromHex};
+use bitcoin::script::Instruction;
+use bitcoin::{
+    Address, AddressType, Amount, BlockHash, Network, OutPoint, ScriptBuf, Sequence, Transaction,
+    TxIn, TxMerkleNode, TxOut, Txid, Witness, absolute, transaction,
+};
+use serde::{Deserialize, Deserializer, Serialize, de::DeserializeOwned};
+
+/// Constructs a type from a JSON string, e.g. to replay a recorded response.
+pub trait FromJson: DeserializeOwned {
+    /// Deserializes `Self` from a string of JSON.
+    fn from_json(s: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(s)
+    }
+}
+
+impl<T: DeserializeOwned> FromJson for T {}
 
 /// Represents response to Get Recommended Fees.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
 pub struct RecommendedFees {
     /// Fastest fee.
-    #[serde(alias = "fastestFee")]
+    #[serde(alias = "fastest_fee")]
     pub fastest_fee: u64,
     /// Half-hour fee.
-    #[serde(alias = "halfHourFee")]
+    #[serde(alias = "half_hour_fee")]
     pub half_hour_fee: u64,
     /// Hour fee.
-    #[serde(alias = "hourFee")]
+    #[serde(alias = "hour_fee")]
     pub hour_fee: u64,
     /// Economy fee.
-    #[serde(alias = "economyFee")]
+    #[serde(alias = "economy_fee")]
     pub economy_fee: u64,
     /// Minimum fee.
-    #[serde(alias = "minimumFee")]
+    #[serde(alias = "minimum_fee")]
     pub minimum_fee: u64,
 }
 
+impl RecommendedFees {
+    /// Returns the recommended fee rate for confirmation within `blocks` blocks.
+    ///
+    /// The target is mapped to a fee bucket as follows:
+    ///
+    /// - `0..=1`: `fastest_fee`
+    /// - `2..=3`: `half_hour_fee`
+    /// - `4..=6`: `hour_fee`
+    /// - `7..=144`: `economy_fee`
+    /// - `145..`: `minimum_fee`
+    pub fn for_target(&self, blocks: u32) -> u64 {
+        match blocks {
+            0..=1 => self.fastest_fee,
+            2..=3 => self.half_hour_fee,
+            4..=6 => self.hour_fee,
+            7..=144 => self.economy_fee,
+            _ => self.minimum_fee,
+        }
+    }
+
+    /// Creates [`RecommendedFees`] from the response to Get Fee Estimates of an Esplora
+    /// backend, which maps a confirmation target in blocks to a fee rate in sat/vB.
+    ///
+    /// Each fee is the estimate for the largest target not greater than `1`, `3`, `6`, `144`
+    /// and `1008` blocks respectively, rounded up. A fee without such an estimate is `1`.
+    pub fn from_fee_estimates(estimates: &HashMap<u16, f64>) -> Self {
+        let fee = |target: u16| {
+            estimates
+                .iter()
+                .filter(|&(&t, _)| t <= target)
+                .max_by_key(|&(&t, _)| t)
+                .map_or(1, |(_, &rate)| rate.ceil() as u64)
+        };
+        Self {
+            fastest_fee: fee(1),
+            half_hour_fee: fee(3),
+            hour_fee: fee(6),
+            economy_fee: fee(144),
+            minimum_fee: fee(1008),
+        }
+    }
+}
+
+/// Represents an element in the response to Get Block Fee Rates.
+///
+/// Each point holds percentiles of the fee rates (sat/vB) paid in the blocks around
+/// `avg_height`.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct FeeSnapshot {
+    /// Average height of the blocks.
+    #[serde(rename = "avgHeight")]
+    pub avg_height: u32,
+    /// Timestamp (UNIX).
+    pub timestamp: u64,
+    /// Minimum fee rate.
+    #[serde(rename = "avgFee_0")]
+    pub avg_fee_0: f64,
+    /// 10th percentile fee rate.
+    #[serde(rename = "avgFee_10")]
+    pub avg_fee_10: f64,
+    /// 25th percentile fee rate.
+    #[serde(rename = "avgFee_25")]
+    pub avg_fee_25: f64,
+    /// Median fee rate.
+    #[serde(rename = "avgFee_50")]
+    pub avg_fee_50: f64,
+    /// 75th percentile fee rate.
+    #[serde(rename = "avgFee_75")]
+    pub avg_fee_75: f64,
+    /// 90th percentile fee rate.
+    #[serde(rename = "avgFee_90")]
+    pub avg_fee_90: f64,
+    /// Maximum fee rate.
+    #[serde(rename = "avgFee_100")]
+    pub avg_fee_100: f64,
+}
+
+/// Represents an element in the response to Get Block Predictions.
+///
+/// The API returns each point as an array of `[timestamp, height, match_rate]`, which is
+/// deserialized in field order.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+pub struct BlockPrediction {
+    /// Timestamp (UNIX).
+    pub timestamp: u64,
+    /// Block height.
+    pub height: u32,
+    /// Percentage of the mined block that matched the block template predicted by the
+    /// server.
+    pub match_rate: f64,
+}
+
+/// Represents an element in the response to Get Mempool Blocks Fees.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MempoolBlock {
+    /// Projected block size in bytes.
+    pub block_size: u64,
+    /// Projected block virtual size.
+    #[serde(rename = "blockVSize")]
+    pub block_vsize: f64,
+    /// Number of transactions in the projected block.
+    pub n_tx: u64,
+    /// Total fees of the projected block (sats).
+    pub total_fees: u64,
+    /// Median fee rate (sat/vB).
+    pub median_fee: f64,
+    /// Fee rates (sat/vB) of the projected block in ascending order, from the minimum to
+    /// the maximum.
+    pub fee_range: Vec<f64>,
+}
+
 /// Element in the response to Get Address Transactions.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct AddressTx {
     /// Transaction ID (txid) as a hex string.
     pub txid: Txid,
@@ -43,20 +176,21 @@ pub struct AddressTx {
     /// Number of signature operations (sigops).
     pub sigops: u32,
     /// Transaction fee in satoshis.
+    #[serde(deserialize_with = "deserialize_u64")]
     pub fee: u64,
     /// Confirmation status and block info.
     pub status: Status,
 }
 
 /// Represents a transaction input (vin).
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct Vin {
     /// Previous transaction ID referenced by this input.
     pub txid: Txid,
     /// Output index in the previous transaction.
     pub vout: u32,
-    /// Previous output details.
-    pub prevout: Vout,
+    /// Previous output details, or `None` for a coinbase input.
+    pub prevout: Option<Vout>,
     /// Script signature as a hex string.
     pub scriptsig: ScriptBuf,
     /// Script signature in ASM format.
@@ -65,10 +199,57 @@ pub struct Vin {
     pub is_coinbase: bool,
     /// Sequence number for this input.
     pub sequence: u64,
+    /// Witness stack items as hex strings. Empty if the input has no witness.
+    #[serde(default)]
+    pub witness: Vec<String>,
+}
+
+impl Vin {
+    /// Returns the [`Address`] of the output spent by this input on the given `network`.
+    ///
+    /// Returns `None` if this is a coinbase input, if the previous output is missing, or if
+    /// the previous output's script has no address form.
+    pub fn prevout_address(&self, network: Network) -> Option<Address> {
+        if self.is_coinbase {
+            return None;
+        }
+        self.prevout.as_ref()?.address(network)
+    }
+
+    /// Whether this input signals replaceability (BIP125), i.e. its sequence number is less
+    /// than `0xfffffffe`.
+    pub fn signals_rbf(&self) -> bool {
+        self.sequence < u64::from(Sequence::ENABLE_LOCKTIME_NO_RBF.0)
+    }
+
+    /// Whether this input has the final sequence number `0xffffffff`, which also disables
+    /// the transaction's locktime if all inputs are final.
+    pub fn is_final(&self) -> bool {
+        self.sequence == u64::from(Sequence::MAX.0)
+    }
+
+    /// Converts this input to a [`TxIn`].
+    fn to_txin(&self) -> Result<TxIn, TxConversionError> {
+        let sequence =
+            u32::try_from(self.sequence).map_err(|_| TxConversionError::Sequence(self.sequence))?;
+        let witness = self
+            .witness
+            .iter()
+            .map(|item| Vec::<u8>::from_hex(item))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(TxConversionError::Witness)?;
+
+        Ok(TxIn {
+            previous_output: OutPoint::new(self.txid, self.vout),
+            script_sig: self.scriptsig.clone(),
+            sequence: Sequence(sequence),
+            witness: Witness::from_slice(&witness),
+        })
+    }
 }
 
 /// Represents a transaction output (vout).
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct Vout {
     /// ScriptPubKey as a hex string.
     pub scriptpubkey: ScriptBuf,
@@ -80,11 +261,43 @@ pub struct Vout {
     #[serde(default)]
     pub scriptpubkey_address: String,
     /// Value of the output in satoshis.
+    #[serde(deserialize_with = "deserialize_u64")]
     pub value: u64,
 }
 
+impl Vout {
+    /// Returns the [`Address`] of this output on the given `network`, or `None` if the
+    /// script has no address form.
+    pub fn address(&self, network: Network) -> Option<Address> {
+        Address::from_script(&self.scriptpubkey, network).ok()
+    }
+
+    /// Returns the data pushed by an `OP_RETURN` output, or `None` if this is not an
+    /// `OP_RETURN` output.
+    ///
+    /// If the script contains more than one push, the pushed data is concatenated in order.
+    pub fn op_return_data(&self) -> Option<Vec<u8>> {
+        if !self.scriptpubkey.is_op_return() {
+            return None;
+        }
+        let data = self
+            .scriptpubkey
+            .instructions()
+            .skip(1)
+            .map_while(Result::ok)
+            .filter_map(|instruction| match instruction {
+                Instruction::PushBytes(bytes) => Some(bytes),
+                _ => None,
+            })
+            .flat_map(|bytes| bytes.as_bytes().iter().copied())
+            .collect();
+
+        Some(data)
+    }
+}
+
 /// Represents the confirmation status and block information for a transaction.
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct Status {
     /// True if the transaction is confirmed.
     pub confirmed: bool,
@@ -97,7 +310,7 @@ pub struct Status {
 }
 
 /// Represents response to Get Mempool.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct MempoolStats {
     /// Number of transactions in the mempool.
     pub count: u64,
@@ -109,9 +322,41 @@ pub struct MempoolStats {
     pub fee_histogram: Vec<(f64, u64)>,
 }
 
+impl MempoolStats {
+    /// Returns the bins of the [`fee_histogram`](Self::fee_histogram).
+    pub fn bins(&self) -> Vec<FeeHistogramBin> {
+        self.fee_histogram
+            .iter()
+            .map(|&(fee_rate, vsize)| FeeHistogramBin { fee_rate, vsize })
+            .collect()
+    }
+}
+
+/// Represents an element in the response to Get Mempool Statistics History.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct MempoolHistoryPoint {
+    /// Timestamp (UNIX).
+    pub timestamp: u64,
+    /// Number of transactions in the mempool.
+    pub count: u32,
+    /// Total virtual size of all mempool transactions.
+    pub vsize: u64,
+    /// Total fees in the mempool (sats).
+    pub total_fee: u64,
+}
+
+/// Bin of the mempool fee histogram.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+pub struct FeeHistogramBin {
+    /// Fee rate (sat/vB) of the bin.
+    pub fee_rate: f64,
+    /// Total virtual size of the transactions in the bin.
+    pub vsize: u64,
+}
+
 /// Represents a Bitcoin transaction from Get Transaction.
 // Note: This is the same as AddressTx.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct TxInfo {
     /// Transaction ID (hex).
     pub txid: Txid,
@@ -130,13 +375,151 @@ pub struct TxInfo {
     /// Number of signature operations.
     pub sigops: u64,
     /// Transaction fee in satoshis.
+    #[serde(deserialize_with = "deserialize_u64")]
     pub fee: u64,
     /// Confirmation status and block info.
     pub status: Status,
 }
 
+impl TxInfo {
+    /// Whether this is a coinbase transaction.
+    pub fn is_coinbase(&self) -> bool {
+        self.vin.iter().any(|vin| vin.is_coinbase)
+    }
+
+    /// Sum of the values of the outputs spent by this transaction in satoshis.
+    ///
+    /// Returns `0` for a coinbase transaction, since it has no previous outputs. Inputs
+    /// whose previous output is missing are counted as `0`.
+    pub fn input_value(&self) -> u64 {
+        if self.is_coinbase() {
+            return 0;
+        }
+        self.vin
+            .iter()
+            .filter_map(|vin| vin.prevout.as_ref())
+            .map(|prevout| prevout.value)
+            .sum()
+    }
+
+    /// Sum of the values of the outputs of this transaction in satoshis.
+    pub fn output_value(&self) -> u64 {
+        self.vout.iter().map(|vout| vout.value).sum()
+    }
+
+    /// Whether the weight of the reconstructed [`Transaction`] matches the reported `weight`.
+    ///
+    /// Returns `false` if the transaction can't be reconstructed, or if the response omitted
+    /// witness data.
+    pub fn check_weight(&self) -> bool {
+        Transaction::try_from(self).is_ok_and(|tx| tx.weight().to_wu() == u64::from(self.weight))
+    }
+}
+
+impl From<AddressTx> for TxInfo {
+    fn from(tx: AddressTx) -> Self {
+        Self {
+            txid: tx.txid,
+            version: tx.version,
+            locktime: tx.locktime,
+            vin: tx.vin,
+            vout: tx.vout,
+            size: tx.size,
+            weight: tx.weight,
+            sigops: tx.sigops.into(),
+            fee: tx.fee,
+            status: tx.status,
+        }
+    }
+}
+
+/// Reconstructs a [`Transaction`] from the JSON form of a transaction.
+///
+/// The witness of each input is taken from [`Vin::witness`], so if the response omitted the
+/// witness data the resulting transaction is unsigned and its wtxid will differ from the
+/// original. The txid doesn't commit to the witness and is always checked.
+impl TryFrom<&TxInfo> for Transaction {
+    type Error = TxConversionError;
+
+    fn try_from(tx: &TxInfo) -> Result<Self, Self::Error> {
+        build_transaction(tx.txid, tx.version, tx.locktime, &tx.vin, &tx.vout)
+    }
+}
+
+/// Reconstructs a [`Transaction`] from the JSON form of a transaction.
+///
+/// See the [`TxInfo`] conversion for the limitations regarding witness data.
+impl TryFrom<&AddressTx> for Transaction {
+    type Error = TxConversionError;
+
+    fn try_from(tx: &AddressTx) -> Result<Self, Self::Error> {
+        build_transaction(tx.txid, tx.version, tx.locktime, &tx.vin, &tx.vout)
+    }
+}
+
+/// Builds a [`Transaction`] from its parts and checks that it has the expected `txid`.
+fn build_transaction(
+    txid: Txid,
+    version: u32,
+    locktime: u32,
+    vin: &[Vin],
+    vout: &[Vout],
+) -> Result<Transaction, TxConversionError> {
+    let tx = Transaction {
+        version: transaction::Version(version as i32),
+        lock_time: absolute::LockTime::from_consensus(locktime),
+        input: vin.iter().map(Vin::to_txin).collect::<Result<_, _>>()?,
+        output: vout
+            .iter()
+            .map(|vout| TxOut {
+                value: Amount::from_sat(vout.value),
+                script_pubkey: vout.scriptpubkey.clone(),
+            })
+            .collect(),
+    };
+    let computed = tx.compute_txid();
+    if computed != txid {
+        return Err(TxConversionError::TxidMismatch {
+            expected: txid,
+            computed,
+        });
+    }
+
+    Ok(tx)
+}
+
+/// Error converting the JSON form of a transaction to a [`Transaction`].
+#[derive(Debug)]
+pub enum TxConversionError {
+    /// A witness item is not valid hex.
+    Witness(hex::HexToBytesError),
+    /// A sequence number doesn't fit in 32 bits.
+    Sequence(u64),
+    /// The txid of the reconstructed transaction doesn't match the reported txid.
+    TxidMismatch {
+        /// The reported txid.
+        expected: Txid,
+        /// The txid of the reconstructed transaction.
+        computed: Txid,
+    },
+}
+
+impl fmt::Display for TxConversionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Witness(e) => write!(f, "invalid witness: {e}"),
+            Self::Sequence(sequence) => write!(f, "invalid sequence number {sequence}"),
+            Self::TxidMismatch { expected, computed } => {
+                write!(f, "txid mismatch: expected {expected}, computed {computed}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for TxConversionError {}
+
 /// Represents a Bitcoin block from Get Block.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct BlockSummary {
     /// Block hash.
     pub id: BlockHash,
@@ -154,8 +537,8 @@ pub struct BlockSummary {
     pub weight: u32,
     /// Merkle root.
     pub merkle_root: TxMerkleNode,
-    /// Previous block hash (hex).
-    pub previousblockhash: BlockHash,
+    /// Previous block hash (hex), or `None` for the genesis block.
+    pub previousblockhash: Option<BlockHash>,
     /// Median time past.
     pub mediantime: u64,
     /// Block nonce.
@@ -167,7 +550,7 @@ pub struct BlockSummary {
 }
 
 /// Represents address details from Get Address.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct AddressInfo {
     /// The address string.
     pub address: String,
@@ -177,23 +560,39 @@ pub struct AddressInfo {
     pub mempool_stats: AddressStats,
 }
 
+impl AddressInfo {
+    /// Returns the [`AddressType`] of the address, or `None` if it can't be parsed or has no
+    /// standard type.
+    pub fn address_type(&self) -> Option<AddressType> {
+        let address: Address<NetworkUnchecked> = self.address.parse().ok()?;
+        address.assume_checked().address_type()
+    }
+
+    /// Whether the address has any confirmed or unconfirmed transactions.
+    pub fn is_used(&self) -> bool {
+        self.chain_stats.tx_count > 0 || self.mempool_stats.tx_count > 0
+    }
+}
+
 /// Represents address statistics.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct AddressStats {
     /// Number of funded outputs.
     pub funded_txo_count: u64,
     /// Sum of funded outputs (sats).
+    #[serde(deserialize_with = "deserialize_u64")]
     pub funded_txo_sum: u64,
     /// Number of spent outputs.
     pub spent_txo_count: u64,
     /// Sum of spent outputs (sats).
+    #[serde(deserialize_with = "deserialize_u64")]
     pub spent_txo_sum: u64,
     /// Number of transactions.
     pub tx_count: u64,
 }
 
 /// Represents a response to Get Transaction Merkle Proof.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct MerkleProof {
     /// Block height.
     pub block_height: u32,
@@ -203,21 +602,75 @@ pub struct MerkleProof {
     pub pos: usize,
 }
 
+impl MerkleProof {
+    /// Computes the merkle root committed to by this proof for the given `txid`.
+    pub fn merkle_root(&self, txid: &Txid) -> TxMerkleNode {
+        let mut pos = self.pos;
+        let mut node = txid.to_raw_hash();
+
+        for sibling in &self.merkle {
+            let mut engine = sha256d::Hash::engine();
+            if pos & 1 == 0 {
+                engine.input(node.as_byte_array());
+                engine.input(sibling.as_byte_array());
+            } else {
+                engine.input(sibling.as_byte_array());
+                engine.input(node.as_byte_array());
+            }
+            node = sha256d::Hash::from_engine(engine);
+            pos >>= 1;
+        }
+
+        TxMerkleNode::from_raw_hash(node)
+    }
+
+    /// Whether the shape of this proof matches a block of `tx_count` transactions, i.e. it
+    /// has one sibling for each level of the merkle tree and `pos` is a valid index.
+    ///
+    /// This must be checked in addition to the merkle root, since a shorter proof can
+    /// present an inner node of the tree as a transaction.
+    pub fn is_consistent_with(&self, tx_count: u32) -> bool {
+        let depth = u64::from(tx_count).next_power_of_two().trailing_zeros();
+        self.merkle.len() == depth as usize
+            && self.pos < tx_count as usize
+            && self.pos.checked_shr(depth).unwrap_or(0) == 0
+    }
+}
+
 /// Represents an element in the response to Get Address UTXO.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct AddressUtxo {
     /// Transaction ID (txid) as a hex string.
     pub txid: Txid,
     /// Output index in the transaction.
     pub vout: u32,
     /// Value of the output in satoshis.
+    #[serde(deserialize_with = "deserialize_u64")]
     pub value: u64,
     /// Status of the UTXO (confirmation info).
     pub status: Status,
 }
 
+impl AddressUtxo {
+    /// Returns the [`OutPoint`] of this UTXO.
+    pub fn out_point(&self) -> OutPoint {
+        OutPoint::new(self.txid, self.vout)
+    }
+
+    /// Returns the [`TxOut`] of this UTXO locked by `script_pubkey`.
+    ///
+    /// The API response doesn't include the script pubkey, so it must be provided by the
+    /// caller. It is usually the `script_pubkey` of the address that was queried.
+    pub fn txout(&self, script_pubkey: ScriptBuf) -> TxOut {
+        TxOut {
+            value: Amount::from_sat(self.value),
+            script_pubkey,
+        }
+    }
+}
+
 /// Represents response to Get Transaction Outspend.
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct OutputStatus {
     /// True if the output has been spent.
     pub spent: bool,
@@ -230,7 +683,7 @@ pub struct OutputStatus {
 }
 
 /// Represents response to Get Block Status.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct BlockStatus {
     /// True if the block is in the best chain.
     pub in_best_chain: bool,
@@ -239,3 +692,435 @@ pub struct BlockStatus {
     /// Block hash of the next block in the best chain.
     pub next_best: Option<BlockHash>,
 }
+
+/// Represents an element in the response to the Get Lightning Node Rankings endpoints.
+#[cfg(feature = "lightning")]
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LnNodeRank {
+    /// Public key of the node.
+    pub public_key: String,
+    /// Alias of the node.
+    pub alias: String,
+    /// Total capacity of the node's channels in satoshis.
+    pub capacity: u64,
+    /// Number of open channels.
+    pub channels: u32,
+    /// Time (UNIX) the node was first seen.
+    pub first_seen: u64,
+    /// Time (UNIX) the node announcement was last updated.
+    pub updated_at: u64,
+}
+
+/// Represents response to Get Block Filter.
+#[cfg(feature = "compact-filters")]
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct BlockFilter {
+    /// BIP158 basic filter as a hex string.
+    pub filter: String,
+    /// Filter header.
+    pub header: bitcoin::bip158::FilterHeader,
+}
+
+#[cfg(feature = "compact-filters")]
+impl BlockFilter {
+    /// Decodes the filter to a [`bip158::BlockFilter`](bitcoin::bip158::BlockFilter).
+    pub fn to_bip158(&self) -> Result<bitcoin::bip158::BlockFilter, hex::HexToBytesError> {
+        let content = Vec::<u8>::from_hex(&self.filter)?;
+        Ok(bitcoin::bip158::BlockFilter::new(&content))
+    }
+}
+
+/// Price of bitcoin in a fiat currency at a point in time, from Get Historical Price.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+pub struct PricePoint {
+    /// Timestamp (UNIX) of the price.
+    pub time: u64,
+    /// Price of one bitcoin in the requested currency.
+    pub price: f64,
+}
+
+/// Represents response to Get Transaction RBF History.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct RbfInfo {
+    /// Tree of replacements of the transaction, or `None` if it wasn't replaced and didn't
+    /// replace another transaction.
+    pub replacements: Option<RbfNode>,
+    /// Txids of the transactions directly replaced by this transaction, if any.
+    pub replaces: Option<Vec<Txid>>,
+}
+
+/// Node in a tree of transaction replacements.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RbfNode {
+    /// The replacing transaction.
+    pub tx: RbfTx,
+    /// Time (UNIX) the replacement was seen.
+    pub time: u64,
+    /// Whether this is a full-RBF replacement, i.e. a replaced transaction didn't signal RBF.
+    #[serde(default)]
+    pub full_rbf: bool,
+    /// Replaced transactions.
+    pub replaces: Vec<RbfNode>,
+}
+
+/// Represents an element in the response to Get Recent Replacements.
+///
+/// The replacing transaction is [`RbfNode::tx`] and the replaced transactions are in
+/// [`RbfNode::replaces`].
+pub type RbfReplacement = RbfNode;
+
+/// Summary of a transaction in a tree of replacements.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct RbfTx {
+    /// Transaction ID.
+    pub txid: Txid,
+    /// Transaction fee in satoshis.
+    pub fee: u64,
+    /// Virtual size of the transaction.
+    pub vsize: f64,
+}
+
+/// Reward paid to the miner of a block, in satoshis.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub struct BlockReward {
+    /// Newly issued coins.
+    pub subsidy: u64,
+    /// Transaction fees, i.e. the coinbase value in excess of the subsidy.
+    pub fees: u64,
+    /// Total value of the coinbase outputs.
+    pub total: u64,
+}
+
+/// Location of a transaction as seen by the server.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TxLocation {
+    /// The transaction is confirmed in the block at `height` with the given `hash`.
+    Confirmed {
+        /// Block height.
+        height: u32,
+        /// Block hash.
+        hash: BlockHash,
+    },
+    /// The transaction is unconfirmed and in the mempool.
+    Mempool,
+    /// The transaction is unknown.
+    Unknown,
+}
+
+/// Represents response to Get Reward Stats.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RewardStats {
+    /// Height of the first block in the range.
+    pub start_block: u32,
+    /// Height of the last block in the range.
+    pub end_block: u32,
+    /// Total reward (subsidy and fees) of the blocks in satoshis.
+    #[serde(deserialize_with = "deserialize_u64")]
+    pub total_reward: u64,
+    /// Total fees of the blocks in satoshis.
+    #[serde(deserialize_with = "deserialize_u64")]
+    pub total_fee: u64,
+    /// Total number of transactions in the blocks.
+    #[serde(deserialize_with = "deserialize_u64")]
+    pub total_tx: u64,
+}
+
+/// Outcome of broadcasting a transaction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BroadcastOutcome {
+    /// The transaction was accepted.
+    Accepted(Txid),
+    /// The transaction is already in the mempool or in the chain.
+    AlreadyKnown(Txid),
+    /// The transaction was rejected.
+    Rejected {
+        /// The reason given by the server.
+        reason: String,
+    },
+}
+
+/// Identifies a block by either its hash or its height in the best chain.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BlockId {
+    /// Block hash.
+    Hash(BlockHash),
+    /// Block height.
+    Height(u32),
+}
+
+impl From<BlockHash> for BlockId {
+    fn from(hash: BlockHash) -> Self {
+        Self::Hash(hash)
+    }
+}
+
+impl From<u32> for BlockId {
+    fn from(height: u32) -> Self {
+        Self::Height(height)
+    }
+}
+
+/// Time period parameter of the mining and statistics endpoints.
+///
+/// Not every endpoint accepts every time period.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TimePeriod {
+    /// `2h`.
+    TwoHours,
+    /// `24h`.
+    OneDay,
+    /// `3d`.
+    ThreeDays,
+    /// `1w`.
+    OneWeek,
+    /// `1m`.
+    OneMonth,
+    /// `3m`.
+    ThreeMonths,
+    /// `6m`.
+    SixMonths,
+    /// `1y`.
+    OneYear,
+    /// `2y`.
+    TwoYears,
+    /// `3y`.
+    ThreeYears,
+    /// `all`.
+    All,
+}
+
+impl TimePeriod {
+    /// Returns the path segment used by the API for this time period.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::TwoHours => "2h",
+            Self::OneDay => "24h",
+            Self::ThreeDays => "3d",
+            Self::OneWeek => "1w",
+            Self::OneMonth => "1m",
+            Self::ThreeMonths => "3m",
+            Self::SixMonths => "6m",
+            Self::OneYear => "1y",
+            Self::TwoYears => "2y",
+            Self::ThreeYears => "3y",
+            Self::All => "all",
+        }
+    }
+}
+
+impl fmt::Display for TimePeriod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Represents an element in the response to Get Mining Pools Hashrates and
+/// Get Mining Pool Hashrate.
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PoolHashratePoint {
+    /// Timestamp (UNIX).
+    pub timestamp: u64,
+    /// Average estimated hashrate (H/s).
+    pub avg_hashrate: f64,
+    /// Share of the network hashrate.
+    pub share: f64,
+    /// Name of the mining pool.
+    pub pool_name: String,
+}
+
+/// Deserializes a `u64` from either a JSON number or a string containing a number, as
+/// returned for amounts by some Esplora forks.
+fn deserialize_u64<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum NumberOrString {
+        Number(u64),
+        String(String),
+    }
+
+    match NumberOrString::deserialize(deserializer)? {
+        NumberOrString::Number(n) => Ok(n),
+        NumberOrString::String(s) => s.parse().map_err(serde::de::Error::custom),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn vout(script_hex: &str) -> Vout {
+        Vout {
+            scriptpubkey: ScriptBuf::from_hex(script_hex).unwrap(),
+            scriptpubkey_asm: String::new(),
+            scriptpubkey_type: String::new(),
+            scriptpubkey_address: String::new(),
+            value: 0,
+        }
+    }
+
+    #[test]
+    fn test_coinbase_prevout_null() {
+        // GET /tx/4a5e1e4baab89f3a32518a88c31bc87f618f76673e2cc77ab2127b7afdeda33b
+        let json = r#"{
+            "txid": "4a5e1e4baab89f3a32518a88c31bc87f618f76673e2cc77ab2127b7afdeda33b",
+            "version": 1,
+            "locktime": 0,
+            "vin": [{
+                "txid": "0000000000000000000000000000000000000000000000000000000000000000",
+                "vout": 4294967295,
+                "prevout": null,
+                "scriptsig": "04ffff001d0104455468652054696d65732030332f4a616e2f32303039204368616e63656c6c6f72206f6e206272696e6b206f66207365636f6e64206261696c6f757420666f722062616e6b73",
+                "scriptsig_asm": "OP_PUSHBYTES_4 ffff001d OP_PUSHBYTES_1 04 OP_PUSHBYTES_69 5468652054696d65732030332f4a616e2f32303039204368616e63656c6c6f72206f6e206272696e6b206f66207365636f6e64206261696c6f757420666f722062616e6b73",
+                "is_coinbase": true,
+                "sequence": 4294967295
+            }],
+            "vout": [{
+                "scriptpubkey": "4104678afdb0fe5548271967f1a67130b7105cd6a828e03909a67962e0ea1f61deb649f6bc3f4cef38c4f35504e51ec112de5c384df7ba0b8d578a4c702b6bf11d5fac",
+                "scriptpubkey_asm": "OP_PUSHBYTES_65 04678afdb0fe5548271967f1a67130b7105cd6a828e03909a67962e0ea1f61deb649f6bc3f4cef38c4f35504e51ec112de5c384df7ba0b8d578a4c702b6bf11d5f OP_CHECKSIG",
+                "scriptpubkey_type": "p2pk",
+                "value": 5000000000
+            }],
+            "size": 204,
+            "weight": 816,
+            "sigops": 4,
+            "fee": 0,
+            "status": {
+                "confirmed": true,
+                "block_height": 0,
+                "block_hash": "000000000019d6689c085ae165831e934ff763ae46a2a6c172b3f1b60a8ce26f",
+                "block_time": 1231006505
+            }
+        }"#;
+
+        let tx: TxInfo = serde_json::from_str(json).unwrap();
+        assert!(tx.is_coinbase());
+        assert_eq!(tx.input_value(), 0);
+        assert!(tx.vin[0].prevout.is_none());
+        assert_eq!(tx.vin[0].prevout_address(Network::Bitcoin), None);
+    }
+
+    #[test]
+    fn test_recommended_fees_spelling() {
+        let camel =
+            r#"{"fastestFee":12,"halfHourFee":10,"hourFee":8,"economyFee":4,"minimumFee":2}"#;
+        let snake =
+            r#"{"fastest_fee":12,"half_hour_fee":10,"hour_fee":8,"economy_fee":4,"minimum_fee":2}"#;
+        for json in [camel, snake] {
+            let fees: RecommendedFees = serde_json::from_str(json).unwrap();
+            assert_eq!(
+                [
+                    fees.fastest_fee,
+                    fees.half_hour_fee,
+                    fees.hour_fee,
+                    fees.economy_fee,
+                    fees.minimum_fee
+                ],
+                [12, 10, 8, 4, 2]
+            );
+        }
+    }
+
+    #[test]
+    fn test_deserialize_u64() {
+        let vout = |value: &str| {
+            serde_json::from_str::<Vout>(&format!(
+                r#"{{"scriptpubkey":"6a","scriptpubkey_asm":"OP_RETURN","scriptpubkey_type":"op_return","value":{value}}}"#
+            ))
+        };
+        assert_eq!(vout("1234").unwrap().value, 1234);
+        assert_eq!(vout(r#""1234""#).unwrap().value, 1234);
+        assert!(vout(r#""abc""#).is_err());
+
+        let stats = |sum: &str| {
+            serde_json::from_str::<AddressStats>(&format!(
+                r#"{{"funded_txo_count":2,"funded_txo_sum":{sum},"spent_txo_count":1,"spent_txo_sum":"1000","tx_count":3}}"#
+            ))
+        };
+        let s = stats("1234").unwrap();
+        assert_eq!((s.funded_txo_sum, s.spent_txo_sum), (1234, 1000));
+        assert_eq!(stats(r#""1234""#).unwrap().funded_txo_sum, 1234);
+        assert!(stats(r#""abc""#).is_err());
+    }
+
+    #[test]
+    fn test_merkle_proof_root() {
+        // GET /tx/f4184fc596403b9d638783cf57adfe4c75c605f6356fbc91338530e9831e9e16/merkle-proof
+        let json = r#"{"block_height":170,"merkle":["b1fea52486ce0c62bb442b530a3f0132b826c74e473d1f2c220bfa78111c5082"],"pos":1}"#;
+        let txid: Txid = "f4184fc596403b9d638783cf57adfe4c75c605f6356fbc91338530e9831e9e16"
+            .parse()
+            .unwrap();
+        // merkle_root of block 170
+        let root: TxMerkleNode = "7dac2c5666815c17a3b36427de37bb9d2e2c5ccec3f8633eb91a4205cb4c10ff"
+            .parse()
+            .unwrap();
+
+        let mut proof: MerkleProof = serde_json::from_str(json).unwrap();
+        assert_eq!(proof.merkle_root(&txid), root);
+
+        // The coinbase is on the left, so its proof has the other txid as sibling.
+        let coinbase = proof.merkle[0];
+        let coinbase_proof = MerkleProof {
+            block_height: 170,
+            merkle: vec![txid],
+            pos: 0,
+        };
+        assert_eq!(coinbase_proof.merkle_root(&coinbase), root);
+
+        proof.pos = 0;
+        assert_ne!(proof.merkle_root(&txid), root);
+        proof.pos = 1;
+        proof.merkle[0] = txid;
+        assert_ne!(proof.merkle_root(&txid), root);
+    }
+
+    #[test]
+    fn test_merkle_proof_shape() {
+        let json = r#"{"block_height":170,"merkle":["b1fea52486ce0c62bb442b530a3f0132b826c74e473d1f2c220bfa78111c5082"],"pos":1}"#;
+        let mut proof: MerkleProof = serde_json::from_str(json).unwrap();
+        assert!(proof.is_consistent_with(2));
+        assert!(!proof.is_consistent_with(3));
+        assert!(!proof.is_consistent_with(1));
+
+        proof.pos = 2;
+        assert!(!proof.is_consistent_with(2));
+
+        // A truncated proof presenting the merkle root itself as the transaction matches
+        // the root, but not the shape of the block.
+        let root: TxMerkleNode = "7dac2c5666815c17a3b36427de37bb9d2e2c5ccec3f8633eb91a4205cb4c10ff"
+            .parse()
+            .unwrap();
+        let truncated = MerkleProof {
+            block_height: 170,
+            merkle: vec![],
+            pos: 0,
+        };
+        let fake_txid = Txid::from_raw_hash(root.to_raw_hash());
+        assert_eq!(truncated.merkle_root(&fake_txid), root);
+        assert!(!truncated.is_consistent_with(2));
+    }
+
+    #[test]
+    fn test_op_return_data() {
+        // OP_RETURN <deadbeef>
+        assert_eq!(
+            vout("6a04deadbeef").op_return_data(),
+            Some(vec![0xde, 0xad, 0xbe, 0xef])
+        );
+        // OP_RETURN <dead> <beef>
+        assert_eq!(
+            vout("6a02dead02beef").op_return_data(),
+            Some(vec![0xde, 0xad, 0xbe, 0xef])
+        );
+        // OP_RETURN
+        assert_eq!(vout("6a").op_return_data(), Some(vec![]));
+        // P2WPKH
+        assert_eq!(
+            vout("0014751e76e8199196d454941c45d1b3a323f1433bd6").op_return_data(),
+            None
+        );
+    }
+}
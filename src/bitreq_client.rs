@@ -1,23 +1,31 @@
 use core::fmt;
+use core::time::Duration;
+use std::sync::{Arc, Mutex};
 
 use bitreq::{Request, Response};
 use bytes::Bytes;
+use tokio::time::Instant;
 
-use crate::{Http, HttpMethod};
+use crate::{AsyncClient, Conditional, ExponentialBackoff, Http, HttpMethod, NoRetry, RetryPolicy};
 
 pub extern crate bitreq;
 pub extern crate tokio;
 
-/// Base backoff in milliseconds.
-const BASE_BACKOFF_MILLIS: u64 = 256;
-/// Default max retries.
-const DEFAULT_MAX_RETRIES: u32 = 6;
-
 /// HTTP client implementation.
+///
+/// The client doesn't own a connection pool, since each request is sent with
+/// [`Request::send_async`], so there is nothing to close and dropping it is sufficient.
 #[derive(Debug, Clone)]
 pub struct BitreqClient {
-    /// The maximum number of times to retry a failed request.
-    max_retries: u32,
+    /// Policy deciding whether and when to retry a failed request.
+    retry_policy: Arc<dyn RetryPolicy>,
+    /// Rate limiter shared by all clones of this client.
+    rate_limiter: Option<Arc<RateLimiter>>,
+    /// Timeout of each request attempt in seconds, if any.
+    timeout: Option<u64>,
+    /// Whether to request compressed response bodies.
+    #[cfg(feature = "gzip")]
+    gzip: bool,
 }
 
 impl BitreqClient {
@@ -32,12 +40,38 @@ impl BitreqClient {
             inner: BitreqClient::new(),
         }
     }
+
+    /// Returns a copy of this client that never retries a failed request.
+    ///
+    /// The copy shares the rate limiter of this client, if any. See also
+    /// [`AsyncClient::no_retry`] to make a single call without retries.
+    pub fn no_retry(&self) -> Self {
+        Self {
+            retry_policy: Arc::new(NoRetry),
+            ..self.clone()
+        }
+    }
+}
+
+impl AsyncClient<BitreqClient> {
+    /// Returns a copy of this client whose requests are never retried by the transport,
+    /// keeping all other configuration, see [`BitreqClient::no_retry`].
+    ///
+    /// This is useful for calls that need to fail fast, e.g. inside a retry loop of the
+    /// caller: `client.no_retry().get_tx(&txid).await`.
+    pub fn no_retry(&self) -> Self {
+        self.with_inner(self.inner().no_retry())
+    }
 }
 
 impl Default for BitreqClient {
     fn default() -> Self {
         Self {
-            max_retries: DEFAULT_MAX_RETRIES,
+            retry_policy: Arc::new(ExponentialBackoff::default()),
+            rate_limiter: None,
+            timeout: None,
+            #[cfg(feature = "gzip")]
+            gzip: false,
         }
     }
 }
@@ -50,10 +84,46 @@ pub struct BitreqClientBuilder {
 }
 
 impl BitreqClientBuilder {
-    /// Set the maximum number of times to retry a request. Note not all failed requests
-    /// are able to be retried.
+    /// Set the maximum number of times to retry a request using the default
+    /// [`ExponentialBackoff`]. Note not all failed requests are able to be retried.
     pub fn retries(mut self, max_retries: u32) -> Self {
-        self.inner.max_retries = max_retries;
+        self.inner.retry_policy = Arc::new(ExponentialBackoff::new(max_retries));
+        self
+    }
+
+    /// Set a custom [`RetryPolicy`], replacing the default [`ExponentialBackoff`].
+    pub fn retry_policy(mut self, policy: impl RetryPolicy + 'static) -> Self {
+        self.inner.retry_policy = Arc::new(policy);
+        self
+    }
+
+    /// Limit the number of requests sent per second, including retries. The limit is shared
+    /// by all clones of the built client.
+    ///
+    /// # Panics
+    ///
+    /// If `requests_per_second` is zero.
+    pub fn rate_limit(mut self, requests_per_second: u32) -> Self {
+        self.inner.rate_limiter = Some(Arc::new(RateLimiter::new(requests_per_second)));
+        self
+    }
+
+    /// Set the timeout of each request attempt, rounded up to whole seconds. By default, or
+    /// if `timeout` is zero, requests don't time out.
+    ///
+    /// The timeout covers the whole attempt from connecting to reading the response body.
+    /// `bitreq` doesn't support a separate connect timeout.
+    pub fn request_timeout(mut self, timeout: Duration) -> Self {
+        let secs = timeout.as_secs() + u64::from(timeout.subsec_nanos() > 0);
+        self.inner.timeout = Some(secs).filter(|&secs| secs > 0);
+        self
+    }
+
+    /// Whether to send `Accept-Encoding: gzip, deflate` and decompress the response body.
+    /// Disabled by default.
+    #[cfg(feature = "gzip")]
+    pub fn gzip(mut self, enable: bool) -> Self {
+        self.inner.gzip = enable;
         self
     }
 
@@ -61,6 +131,14 @@ impl BitreqClientBuilder {
     pub fn build(self) -> BitreqClient {
         self.inner
     }
+
+    /// Returns a `bitreq` client without consuming the builder, so that it can be used to
+    /// build several clients from a shared configuration.
+    ///
+    /// Clients built from the same builder share its rate limit, if any.
+    pub fn build_ref(&self) -> BitreqClient {
+        self.inner.clone()
+    }
 }
 
 impl Http for BitreqClient {
@@ -77,56 +155,201 @@ impl Http for BitreqClient {
     where
         Self: 'a,
     {
-        let resp = self.send_retry(method.into(), url, body.into()).await?;
+        self.send_with_headers(method, url, &[], body).await
+    }
 
-        if !is_status_ok(resp.status_code) {
-            return Err(BitreqError::HttpResponse {
-                status: resp.status_code,
-                message: resp.reason_phrase,
-            });
+    async fn send_with_headers<'a>(
+        &'a self,
+        method: HttpMethod,
+        url: &'a str,
+        headers: &'a [(&'a str, &'a str)],
+        body: impl Into<Self::Body>,
+    ) -> Result<Self::Body, Self::Err>
+    where
+        Self: 'a,
+    {
+        let resp = self.send_retry(method.into(), url, headers, body.into()).await?;
+
+        Ok(into_body(resp)?.into())
+    }
+
+    async fn get_conditional<'a>(
+        &'a self,
+        url: &'a str,
+        etag: Option<&'a str>,
+    ) -> Result<Conditional<Self::Body>, Self::Err>
+    where
+        Self: 'a,
+    {
+        let header = etag.map(|etag| ("If-None-Match", etag));
+        let resp = self
+            .send_retry(bitreq::Method::Get, url, header.as_slice(), Bytes::new())
+            .await?;
+
+        if resp.status_code == 304 {
+            return Ok(Conditional::NotModified);
         }
+        let etag = resp.headers.get("etag").cloned();
+        let body = into_body(resp)?.into();
 
-        Ok(resp.into_bytes().into())
+        Ok(Conditional::Modified { body, etag })
+    }
+
+    fn error_status(err: &Self::Err) -> Option<u16> {
+        match err {
+            BitreqError::HttpResponse { status, .. } => u16::try_from(*status).ok(),
+            _ => None,
+        }
+    }
+
+    fn error_message(err: &Self::Err) -> Option<&str> {
+        match err {
+            BitreqError::HttpResponse { message, .. } => Some(message),
+            _ => None,
+        }
     }
 }
 
 impl BitreqClient {
-    /// Sends a request and allows for retrying failed attempts. See [`is_status_retryable`].
+    /// Sends a request and allows for retrying failed attempts according to the
+    /// configured [`RetryPolicy`].
     async fn send_retry(
         &self,
         method: bitreq::Method,
         url: &str,
+        headers: &[(&str, &str)],
         body: Bytes,
     ) -> Result<Response, bitreq::Error> {
-        let mut delay = BASE_BACKOFF_MILLIS;
         let mut attempts = 0;
 
         loop {
-            match Request::new(method.clone(), url)
-                .with_body(body.clone())
-                .send_async()
-                .await?
-            {
-                resp if attempts < self.max_retries && is_status_retryable(resp.status_code) => {
-                    tokio::time::sleep(std::time::Duration::from_millis(delay)).await;
-                    delay *= 2;
+            if let Some(rate_limiter) = &self.rate_limiter {
+                rate_limiter.acquire().await;
+            }
+            let mut req = Request::new(method.clone(), url).with_body(body.clone());
+            for &(key, value) in headers {
+                req = req.with_header(key, value);
+            }
+            if let Some(secs) = self.timeout {
+                req = req.with_timeout(secs);
+            }
+            #[cfg(feature = "gzip")]
+            let req = if self.gzip {
+                req.with_header("Accept-Encoding", "gzip, deflate")
+            } else {
+                req
+            };
+            #[cfg(feature = "log")]
+            log::debug!("Sending {method:?} {url}");
+            let res = req.send_async().await;
+            #[cfg(feature = "log")]
+            match &res {
+                Ok(resp) => log::debug!(
+                    "Received {} from {url} ({} bytes)",
+                    resp.status_code,
+                    resp.as_bytes().len()
+                ),
+                Err(e) => log::debug!("Request to {url} failed: {e}"),
+            }
+            let status = match &res {
+                Ok(resp) if is_status_ok(resp.status_code) || resp.status_code == 304 => {
+                    return res;
+                }
+                Ok(resp) => u16::try_from(resp.status_code).ok(),
+                Err(_) => None,
+            };
+            match self.retry_policy.next_delay(attempts, status) {
+                Some(delay) => {
+                    #[cfg(feature = "log")]
+                    log::debug!("Retrying {url} in {delay:?} (retry {})", attempts + 1);
+                    #[cfg(feature = "tracing")]
+                    tracing::debug!(url, ?delay, retry = attempts + 1, ?status, "retrying request");
+                    tokio::time::sleep(delay).await;
                     attempts += 1;
                 }
-                resp => return Ok(resp),
+                None => return res,
             }
         }
     }
 }
 
-/// Whether the response status indicates a failure which can be retried.
-///
-/// Currently includes:
-///
-/// - `429`: TOO_MANY_REQUESTS
-/// - `500`: INTERNAL_SERVER_ERROR
-/// - `503`: SERVICE_UNAVAILABLE
-fn is_status_retryable(status: i32) -> bool {
-    [429, 500, 503].contains(&status)
+/// Spaces out requests evenly so that no more than a fixed number are sent per second.
+#[derive(Debug)]
+struct RateLimiter {
+    /// Minimum interval between two requests.
+    interval: Duration,
+    /// The earliest instant at which the next request may be sent.
+    next: Mutex<Instant>,
+}
+
+impl RateLimiter {
+    /// New with the given number of `requests_per_second`.
+    ///
+    /// # Panics
+    ///
+    /// If `requests_per_second` is zero.
+    fn new(requests_per_second: u32) -> Self {
+        Self {
+            interval: Duration::from_secs(1) / requests_per_second,
+            next: Mutex::new(Instant::now()),
+        }
+    }
+
+    /// Waits until the next request is permitted to be sent.
+    async fn acquire(&self) {
+        let slot = {
+            let mut next = self.next.lock().unwrap_or_else(|e| e.into_inner());
+            let slot = Instant::now().max(*next);
+            *next = slot + self.interval;
+            slot
+        };
+        tokio::time::sleep_until(slot).await;
+    }
+}
+
+/// Returns the body of a successful response, or an error if the status is not `200 OK`.
+fn into_body(resp: Response) -> Result<Vec<u8>, BitreqError> {
+    if !is_status_ok(resp.status_code) {
+        let body = resp.as_str().map(str::trim).unwrap_or_default().to_string();
+        return Err(BitreqError::HttpResponse {
+            status: resp.status_code,
+            message: if body.is_empty() {
+                resp.reason_phrase
+            } else {
+                body
+            },
+        });
+    }
+
+    decode_body(resp)
+}
+
+/// Returns the response body, decompressing it according to the `Content-Encoding` header.
+#[cfg(feature = "gzip")]
+fn decode_body(resp: Response) -> Result<Vec<u8>, BitreqError> {
+    use std::io::Read;
+
+    let encoding = resp
+        .headers
+        .get("content-encoding")
+        .map(|s| s.trim().to_ascii_lowercase());
+    let body = resp.into_bytes();
+
+    let mut buf = vec![];
+    match encoding.as_deref() {
+        Some("gzip") => flate2::read::GzDecoder::new(&body[..]).read_to_end(&mut buf),
+        Some("deflate") => flate2::read::ZlibDecoder::new(&body[..]).read_to_end(&mut buf),
+        _ => return Ok(body),
+    }
+    .map_err(BitreqError::Decompression)?;
+
+    Ok(buf)
+}
+
+/// Returns the response body.
+#[cfg(not(feature = "gzip"))]
+fn decode_body(resp: Response) -> Result<Vec<u8>, BitreqError> {
+    Ok(resp.into_bytes())
 }
 
 /// Whether the response status code is `200 OK`.
@@ -139,8 +362,10 @@ fn is_status_ok(status: i32) -> bool {
 pub enum BitreqError {
     /// `bitreq` error.
     Bitreq(bitreq::Error),
-    /// Reponse error.
+    /// Reponse error, with the response body or the reason phrase if the body is empty.
     HttpResponse { status: i32, message: String },
+    /// Failed to decompress the response body according to its `Content-Encoding`.
+    Decompression(std::io::Error),
 }
 
 impl fmt::Display for BitreqError {
@@ -148,6 +373,7 @@ impl fmt::Display for BitreqError {
         match self {
             Self::Bitreq(e) => write!(f, "{e}"),
             Self::HttpResponse { status, message } => write!(f, "{status} {message}"),
+            Self::Decompression(e) => write!(f, "failed to decompress response body: {e}"),
         }
     }
 }
@@ -159,3 +385,35 @@ impl From<bitreq::Error> for BitreqError {
         Self::Bitreq(e)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_request_timeout() {
+        let timeout = |timeout| BitreqClient::builder().request_timeout(timeout).build().timeout;
+        assert_eq!(timeout(Duration::ZERO), None);
+        assert_eq!(timeout(Duration::from_millis(1)), Some(1));
+        assert_eq!(timeout(Duration::from_secs(5)), Some(5));
+        assert_eq!(timeout(Duration::from_millis(5_500)), Some(6));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_rate_limiter() {
+        let limiter = RateLimiter::new(4);
+        let start = Instant::now();
+        let mut elapsed = vec![];
+        for _ in 0..5 {
+            limiter.acquire().await;
+            elapsed.push(start.elapsed().as_millis());
+        }
+        assert_eq!(elapsed, [0, 250, 500, 750, 1000]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_rate_limit_zero() {
+        let _ = BitreqClient::builder().rate_limit(0);
+    }
+}
@@ -0,0 +1,62 @@
+//! [`EtagCache`].
+
+use core::fmt;
+use std::collections::HashMap;
+use std::sync::{Mutex, MutexGuard};
+
+/// In-memory cache of response bodies keyed by URL, along with their `ETag`.
+pub(crate) struct EtagCache {
+    /// The maximum number of entries.
+    capacity: usize,
+    /// Map of URL to `(etag, body)`.
+    entries: Mutex<HashMap<String, (String, Vec<u8>)>>,
+}
+
+impl fmt::Debug for EtagCache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EtagCache")
+            .field("capacity", &self.capacity)
+            .field("len", &self.entries().len())
+            .finish()
+    }
+}
+
+impl EtagCache {
+    /// New with the given `capacity`.
+    pub(crate) fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Locks the cache entries.
+    fn entries(&self) -> MutexGuard<'_, HashMap<String, (String, Vec<u8>)>> {
+        self.entries.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Returns the cached `ETag` for `url`.
+    pub(crate) fn etag(&self, url: &str) -> Option<String> {
+        self.entries().get(url).map(|(etag, _)| etag.clone())
+    }
+
+    /// Returns the cached body for `url`.
+    pub(crate) fn body(&self, url: &str) -> Option<Vec<u8>> {
+        self.entries().get(url).map(|(_, body)| body.clone())
+    }
+
+    /// Inserts the `etag` and `body` for `url`, evicting an arbitrary entry if the cache
+    /// is full.
+    pub(crate) fn insert(&self, url: &str, etag: String, body: Vec<u8>) {
+        if self.capacity == 0 {
+            return;
+        }
+        let mut entries = self.entries();
+        if entries.len() >= self.capacity && !entries.contains_key(url) {
+            if let Some(key) = entries.keys().next().cloned() {
+                entries.remove(&key);
+            }
+        }
+        entries.insert(url.to_string(), (etag, body));
+    }
+}
@@ -1,57 +1,492 @@
 //! [`AsyncClient`].
 
 use core::fmt::{self, Debug};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::Arc;
 
 use bitcoin::{
-    Address, Block, BlockHash, MerkleBlock, Script, Transaction, Txid,
+    Address, Block, BlockHash, MerkleBlock, Network, Script, ScriptBuf, Transaction, Txid,
     block::Header,
     consensus,
     hashes::{Hash, sha256},
+    hex::FromHex,
 };
+use futures::{Stream, StreamExt, TryStreamExt, stream};
+use serde::de::DeserializeOwned;
 
 use crate::Error;
 use crate::api::{
-    AddressInfo, AddressTx, AddressUtxo, BlockStatus, BlockSummary, MempoolStats, MerkleProof,
-    OutputStatus, RecommendedFees, Status, TxInfo,
+    AddressInfo, AddressTx, AddressUtxo, BlockId, BlockPrediction, BlockReward, BlockStatus,
+    BlockSummary, BroadcastOutcome, FeeSnapshot, MempoolBlock, MempoolHistoryPoint, MempoolStats,
+    MerkleProof, OutputStatus, PoolHashratePoint, PricePoint, RbfInfo, RbfReplacement,
+    RecommendedFees, RewardStats, Status, TimePeriod, TxInfo, TxLocation, Vin, Vout,
 };
-use crate::http::{Http, HttpMethod as Method};
+use crate::cache::EtagCache;
+use crate::http::{Conditional, Http, HttpMethod as Method};
+
+/// Default maximum number of concurrent requests made by batch methods.
+const DEFAULT_CONCURRENCY: usize = 4;
+
+/// Rejection reasons given by `sendrawtransaction` for a transaction that is already known.
+const ALREADY_KNOWN_REASONS: &[&str] = &[
+    "txn-already-in-mempool",
+    "txn-already-known",
+    "Transaction already in block chain",
+    "Transaction outputs already in utxo set",
+];
+
+/// Number of transactions per page returned by `/block/:hash/txs/:start_index`.
+const BLOCK_TXS_PAGE_LEN: usize = 25;
+
+/// Number of blocks per page returned by `/blocks/:height` on mempool.space.
+const BLOCKS_PAGE_LEN: u32 = 15;
+
+/// Maximum number of blocks walked back from the tip by
+/// [`new_blocks_stream`](AsyncClient::new_blocks_stream) when looking for a known block.
+#[cfg(feature = "tokio")]
+const MAX_CATCH_UP_DEPTH: usize = 10;
+
+/// Interval between polls of the transaction status by
+/// [`broadcast_and_confirm_in_mempool`](AsyncClient::broadcast_and_confirm_in_mempool).
+#[cfg(feature = "tokio")]
+const MEMPOOL_POLL_INTERVAL: core::time::Duration = core::time::Duration::from_secs(1);
+
+/// Kind of backend serving the API, which determines the paths of some endpoints.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Backend {
+    /// A mempool.space instance.
+    #[default]
+    MempoolSpace,
+    /// A plain Esplora (electrs) instance.
+    ///
+    /// Endpoints aren't prefixed with `/v1`, recommended fees are derived from
+    /// `/fee-estimates`, and address transactions are paged with `/txs/chain/:txid`.
+    /// Endpoints specific to mempool.space, e.g. mining statistics, aren't available.
+    Esplora,
+}
+
+/// Function that rewrites the URL of a request before it is sent.
+pub type RequestHook = dyn Fn(&Method, &str) -> String + Send + Sync;
+
+/// Function that observes the URL and raw body of each successful response.
+pub type ResponseRecorder = dyn Fn(&str, &[u8]) + Send + Sync;
 
 /// Async client that is generic over the [`Http`] implementation.
 pub struct AsyncClient<T> {
-    /// Base url
-    pub url: String,
+    /// Base url, without a trailing `/`.
+    url: String,
+    /// Base urls tried in order when a request to `url` fails, without a trailing `/`.
+    fallbacks: Vec<String>,
     /// inner HTTP client.
     inner: T,
+    /// Cache of immutable responses, if enabled.
+    etag_cache: Option<Arc<EtagCache>>,
+    /// Whether fee, mining and price endpoints are prefixed with `/v1`.
+    v1_prefix: bool,
+    /// Kind of backend serving the API.
+    backend: Backend,
+    /// Network of the connected instance, if known.
+    network: Option<Network>,
+    /// Rewrites the URL of each request, if set.
+    request_hook: Option<Arc<RequestHook>>,
+    /// Observes each successful response, if set.
+    response_recorder: Option<Arc<ResponseRecorder>>,
+    /// Headers sent with each request, as `(name, value)`.
+    headers: Vec<(String, String)>,
 }
 
 impl<T: Debug> Debug for AsyncClient<T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("AsyncClient")
             .field("url", &self.url)
+            .field("fallbacks", &self.fallbacks)
             .field("inner", &self.inner)
+            .field("etag_cache", &self.etag_cache)
+            .field("v1_prefix", &self.v1_prefix)
+            .field("backend", &self.backend)
+            .field("network", &self.network)
+            .field("request_hook", &self.request_hook.is_some())
+            .field("response_recorder", &self.response_recorder.is_some())
+            .field(
+                "headers",
+                &self.headers.iter().map(|(name, _)| name).collect::<Vec<_>>(),
+            )
             .finish()
     }
 }
 
 impl<T: Http> AsyncClient<T> {
     /// New.
+    ///
+    /// A trailing `/` is removed from the base `url`, so that paths are joined with exactly
+    /// one separator.
     pub fn new(url: &str, inner: T) -> Self {
         Self {
-            url: url.to_string(),
+            url: url.trim_end_matches('/').to_string(),
+            fallbacks: vec![],
             inner,
+            etag_cache: None,
+            v1_prefix: true,
+            backend: Backend::MempoolSpace,
+            network: None,
+            request_hook: None,
+            response_recorder: None,
+            headers: vec![],
+        }
+    }
+
+    /// New client using the first of `urls` as the base URL and the others as fallbacks.
+    ///
+    /// When a request fails without a response, or with a `5xx` status after the retries of
+    /// the `inner` client are exhausted, the same request is sent to the next URL in order.
+    ///
+    /// Recognizing the status requires an [`Http`] implementation that supports
+    /// [`Http::error_status`]. With the default implementation, which returns `None`, every
+    /// error is taken to have no response, so e.g. a `404 Not Found` or a rejected broadcast
+    /// is also sent to each fallback.
+    ///
+    /// # Panics
+    ///
+    /// If `urls` is empty.
+    pub fn with_fallbacks(urls: &[&str], inner: T) -> Self {
+        let (url, fallbacks) = urls.split_first().expect("at least one URL");
+        let mut client = Self::new(url, inner);
+        client.fallbacks = fallbacks
+            .iter()
+            .map(|url| url.trim_end_matches('/').to_string())
+            .collect();
+        client
+    }
+
+    /// Creates an [`AsyncClient`] builder using the `inner` HTTP client.
+    ///
+    /// The base URL defaults to [`MAINNET_URL`](crate::MAINNET_URL). No network is set unless
+    /// [`network`](AsyncClientBuilder::network) is called. Transport options such as retries are
+    /// configured on the `inner` client.
+    pub fn builder(inner: T) -> AsyncClientBuilder<T> {
+        AsyncClientBuilder {
+            inner: Self::new(crate::MAINNET_URL, inner),
+        }
+    }
+
+    /// New client for the mempool.space mainnet API at [`MAINNET_URL`](crate::MAINNET_URL).
+    pub fn mainnet(inner: T) -> Self {
+        Self::new(crate::MAINNET_URL, inner).with_network(Network::Bitcoin)
+    }
+
+    /// New client for the mempool.space signet API at [`SIGNET_URL`](crate::SIGNET_URL).
+    pub fn signet(inner: T) -> Self {
+        Self::new(crate::SIGNET_URL, inner).with_network(Network::Signet)
+    }
+
+    /// Consumes the client and returns the inner HTTP client.
+    ///
+    /// This allows cleaning up the transport deterministically, e.g. by shutting down its
+    /// connection pool, rather than relying on `Drop`.
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+
+    /// Returns a reference to the inner HTTP client.
+    pub fn inner(&self) -> &T {
+        &self.inner
+    }
+
+    /// Returns a client with the same configuration that sends requests with `inner`
+    /// instead, e.g. a copy of the transport with different retry settings.
+    ///
+    /// The ETag cache, if enabled, is shared with this client.
+    pub fn with_inner<U: Http>(&self, inner: U) -> AsyncClient<U> {
+        AsyncClient {
+            url: self.url.clone(),
+            fallbacks: self.fallbacks.clone(),
+            inner,
+            etag_cache: self.etag_cache.clone(),
+            v1_prefix: self.v1_prefix,
+            backend: self.backend,
+            network: self.network,
+            request_hook: self.request_hook.clone(),
+            response_recorder: self.response_recorder.clone(),
+            headers: self.headers.clone(),
+        }
+    }
+
+    /// Returns the base URL of the API, without a trailing `/`.
+    pub fn base_url(&self) -> &str {
+        &self.url
+    }
+
+    /// Set a `hook` that rewrites the URL of each request before it is sent, e.g. to route
+    /// requests through a proxy or to add query parameters.
+    ///
+    /// The hook receives the method and the full URL, including the base URL, and returns
+    /// the URL to send the request to. It is applied to fallback URLs as well.
+    pub fn with_request_hook(
+        mut self,
+        hook: impl Fn(&Method, &str) -> String + Send + Sync + 'static,
+    ) -> Self {
+        self.request_hook = Some(Arc::new(hook));
+        self
+    }
+
+    /// Set a `recorder` that is called with the URL and raw body of each successful response
+    /// before it is decoded, e.g. to capture fixtures for tests.
+    ///
+    /// Bodies returned from the ETag cache on `304 Not Modified` aren't recorded again.
+    pub fn with_response_recorder(
+        mut self,
+        recorder: impl Fn(&str, &[u8]) + Send + Sync + 'static,
+    ) -> Self {
+        self.response_recorder = Some(Arc::new(recorder));
+        self
+    }
+
+    /// Add a header that is sent with each request, e.g. an API key.
+    ///
+    /// Headers are sent with [`Http::send_with_headers`], so they require an [`Http`]
+    /// implementation that supports it. Since [`Http::get_conditional`] doesn't take headers,
+    /// the ETag cache is bypassed while any header is set.
+    pub fn with_header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    /// Set the [`Network`] of the connected instance, which is used to parse addresses in
+    /// responses, e.g. by [`parse_address_in`](Self::parse_address_in).
+    pub fn with_network(mut self, network: Network) -> Self {
+        self.network = Some(network);
+        self
+    }
+
+    /// Returns the [`Network`] of the connected instance, if it was set.
+    pub fn network(&self) -> Option<Network> {
+        self.network
+    }
+
+    /// Returns the [`Address`] of `vout` on the network of this client.
+    ///
+    /// Returns `None` if the network wasn't set or if the script has no address form.
+    pub fn parse_address_in(&self, vout: &Vout) -> Option<Address> {
+        vout.address(self.network?)
+    }
+
+    /// Returns the [`Address`] of the output spent by `vin` on the network of this client.
+    ///
+    /// Returns `None` if the network wasn't set, see [`Vin::prevout_address`].
+    pub fn parse_prevout_address_in(&self, vin: &Vin) -> Option<Address> {
+        vin.prevout_address(self.network?)
+    }
+
+    /// Set the kind of [`Backend`] serving the API. Defaults to [`Backend::MempoolSpace`].
+    ///
+    /// This also sets whether endpoints are prefixed with `/v1`, see
+    /// [`with_v1_prefix`](Self::with_v1_prefix).
+    pub fn with_backend(mut self, backend: Backend) -> Self {
+        self.backend = backend;
+        self.v1_prefix = backend == Backend::MempoolSpace;
+        self
+    }
+
+    /// Set whether fee, mining and price endpoints are prefixed with `/v1`. Defaults to `true`
+    /// as expected by mempool.space. Disable it for backends that serve e.g. `/fees/recommended`.
+    pub fn with_v1_prefix(mut self, v1_prefix: bool) -> Self {
+        self.v1_prefix = v1_prefix;
+        self
+    }
+
+    /// Enable caching of immutable block data, holding at most `capacity` responses.
+    ///
+    /// When enabled, [`get_block`](Self::get_block), [`get_block_header`](Self::get_block_header)
+    /// and [`get_block_txids`](Self::get_block_txids) send the `ETag` of a cached response in
+    /// an `If-None-Match` header and return the cached body on `304 Not Modified`. This requires
+    /// an [`Http`] implementation that supports [`Http::get_conditional`]. Conditional requests
+    /// fail over to the fallback URLs like any other request. The cache is bypassed while a
+    /// header is set, see [`with_header`](Self::with_header).
+    pub fn with_etag_cache(mut self, capacity: usize) -> Self {
+        self.etag_cache = Some(Arc::new(EtagCache::new(capacity)));
+        self
+    }
+
+    /// Runs `fut`, e.g. an operation made up of many requests, and errors with
+    /// [`Error::Timeout`] if it doesn't complete within `timeout`.
+    ///
+    /// Any requests still in flight when the timeout elapses are dropped.
+    #[cfg(feature = "tokio")]
+    pub async fn with_timeout<F, R>(
+        &self,
+        timeout: core::time::Duration,
+        fut: F,
+    ) -> Result<R, Error<T::Err>>
+    where
+        F: core::future::Future<Output = Result<R, Error<T::Err>>>,
+    {
+        tokio::time::timeout(timeout, fut)
+            .await
+            .unwrap_or_else(|_| Err(Error::Timeout))
+    }
+
+    /// Runs `fut`, e.g. [`hydrate_mempool`](Self::hydrate_mempool), until it completes or
+    /// `token` is cancelled, in which case it errors with [`Error::Cancelled`].
+    ///
+    /// Any requests still in flight when the token is cancelled are dropped.
+    #[cfg(feature = "tokio-util")]
+    pub async fn with_cancellation<F, R>(
+        &self,
+        token: &tokio_util::sync::CancellationToken,
+        fut: F,
+    ) -> Result<R, Error<T::Err>>
+    where
+        F: core::future::Future<Output = Result<R, Error<T::Err>>>,
+    {
+        token.run_until_cancelled(fut).await.unwrap_or(Err(Error::Cancelled))
+    }
+
+    /// Returns the URL of a fee, mining or price endpoint at `path`, including the `/v1`
+    /// prefix if enabled.
+    fn v1_url(&self, path: &str) -> String {
+        if self.v1_prefix {
+            format!("{}/v1{path}", self.url)
+        } else {
+            format!("{}{path}", self.url)
         }
     }
 
     /// Sends a GET request to the given `path` with an empty body.
     async fn get(&self, path: &str) -> Result<T::Body, T::Err> {
-        self.inner.send(Method::GET, path, vec![]).await
+        self.request(Method::GET, path, vec![]).await
+    }
+
+    /// Sends a request to the given `path`, retrying it against the fallback URLs in order
+    /// if it fails without a response or with a `5xx` status.
+    async fn request(&self, method: Method, path: &str, body: Vec<u8>) -> Result<T::Body, T::Err> {
+        self.with_fallback_urls(method, path, |url| {
+            let body = body.clone();
+            async move { self.send_to(method, &url, body).await }
+        })
+        .await
+    }
+
+    /// Calls `send` with the URL of `path`, and again with the same path on each fallback URL
+    /// in order while it fails without a response or with a `5xx` status.
+    async fn with_fallback_urls<F, Fut, R>(
+        &self,
+        method: Method,
+        path: &str,
+        send: F,
+    ) -> Result<R, T::Err>
+    where
+        F: Fn(String) -> Fut,
+        Fut: core::future::Future<Output = Result<R, T::Err>>,
+    {
+        let mut res = send(self.rewrite(&method, path)).await;
+        let Some(suffix) = path.strip_prefix(self.url.as_str()) else {
+            return res;
+        };
+        for url in &self.fallbacks {
+            match &res {
+                Err(e) if T::error_status(e).is_none_or(|status| status >= 500) => {}
+                _ => break,
+            }
+            res = send(self.rewrite(&method, &format!("{url}{suffix}"))).await;
+        }
+
+        res
+    }
+
+    /// Sends a request to `url` and passes a successful response to the response recorder.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "debug", skip(self, body), err)
+    )]
+    async fn send_to(&self, method: Method, url: &str, body: Vec<u8>) -> Result<T::Body, T::Err> {
+        let res = if self.headers.is_empty() {
+            self.inner.send(method, url, body).await
+        } else {
+            let headers: Vec<(&str, &str)> = self
+                .headers
+                .iter()
+                .map(|(name, value)| (name.as_str(), value.as_str()))
+                .collect();
+            self.inner.send_with_headers(method, url, &headers, body).await
+        };
+        if let (Ok(body), Some(recorder)) = (&res, &self.response_recorder) {
+            recorder(url, body.as_ref());
+        }
+
+        res
+    }
+
+    /// Returns the URL to send a request for `url` to, applying the request hook if set.
+    fn rewrite(&self, method: &Method, url: &str) -> String {
+        match &self.request_hook {
+            Some(hook) => hook(method, url),
+            None => url.to_string(),
+        }
+    }
+
+    /// Sends a GET request to the given `path`, using the ETag cache if it is enabled.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "debug", skip(self), err)
+    )]
+    async fn get_cached(&self, path: &str) -> Result<T::Body, T::Err> {
+        let Some(cache) = self.etag_cache.as_ref().filter(|_| self.headers.is_empty()) else {
+            return self.get(path).await;
+        };
+        let etag = cache.etag(path);
+        let res = self
+            .with_fallback_urls(Method::GET, path, |url| {
+                let etag = etag.as_deref();
+                async move {
+                    let res = self.inner.get_conditional(&url, etag).await?;
+                    if let (Conditional::Modified { body, .. }, Some(recorder)) =
+                        (&res, &self.response_recorder)
+                    {
+                        recorder(&url, body.as_ref());
+                    }
+                    Ok(res)
+                }
+            })
+            .await?;
+        match res {
+            Conditional::Modified { body, etag } => {
+                if let Some(etag) = etag {
+                    cache.insert(path, etag, body.as_ref().to_vec());
+                }
+                Ok(body)
+            }
+            Conditional::NotModified => match cache.body(path) {
+                Some(body) => Ok(body.into()),
+                None => self.get(path).await,
+            },
+        }
+    }
+
+    /// Maps an error caused by a `404 Not Found` response to `Ok(None)`.
+    fn not_found_as_none<U>(res: Result<U, Error<T::Err>>) -> Result<Option<U>, Error<T::Err>> {
+        match res {
+            Ok(value) => Ok(Some(value)),
+            Err(Error::Http(e)) if T::error_status(&e) == Some(404) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// GET `/:path_suffix`, returning the untyped JSON response.
+    ///
+    /// This is useful for exploring endpoints that don't have a typed method yet, e.g.
+    /// `client.get_value("v1/mining/pools/1w")`.
+    pub async fn get_value(&self, path_suffix: &str) -> Result<serde_json::Value, Error<T::Err>> {
+        let path = format!("{}/{}", self.url, path_suffix.trim_start_matches('/'));
+        let body = self.get(&path).await.map_err(Error::Http)?;
+
+        deserialize_json(&path, body.as_ref())
     }
 
     /// GET `/blocks/tip/hash`.
     pub async fn get_tip_hash(&self) -> Result<BlockHash, Error<T::Err>> {
         let path = format!("{}/blocks/tip/hash", self.url);
         let body = self.get(&path).await.map_err(Error::Http)?;
-        let s = String::from_utf8_lossy(body.as_ref());
+        let s = core::str::from_utf8(body.as_ref()).map_err(Error::Utf8)?;
 
         s.parse().map_err(Error::HexToArray)
     }
@@ -60,7 +495,7 @@ impl<T: Http> AsyncClient<T> {
     pub async fn get_tip_height(&self) -> Result<u32, Error<T::Err>> {
         let path = format!("{}/blocks/tip/height", self.url);
         let body = self.get(&path).await.map_err(Error::Http)?;
-        let s = String::from_utf8_lossy(body.as_ref());
+        let s = core::str::from_utf8(body.as_ref()).map_err(Error::Utf8)?;
 
         s.parse::<u32>().map_err(Error::ParseInt)
     }
@@ -69,18 +504,62 @@ impl<T: Http> AsyncClient<T> {
     pub async fn get_block_hash(&self, height: u32) -> Result<BlockHash, Error<T::Err>> {
         let path = format!("{}/block-height/{height}", self.url);
         let body = self.get(&path).await.map_err(Error::Http)?;
-        let s = String::from_utf8_lossy(body.as_ref());
+        let s = core::str::from_utf8(body.as_ref()).map_err(Error::Utf8)?;
 
         s.parse().map_err(Error::HexToArray)
     }
 
+    /// Get the timestamp (UNIX) of the tip, as reported by [`BlockSummary::timestamp`].
+    pub async fn get_tip_timestamp(&self) -> Result<u64, Error<T::Err>> {
+        let hash = self.get_tip_hash().await?;
+
+        Ok(self.get_block_info(&hash).await?.timestamp)
+    }
+
+    /// Get the hash of the block `depth` blocks below the tip, i.e. at `tip_height - depth`.
+    ///
+    /// A depth of `0` is the tip itself, so the block with `n` confirmations is at depth
+    /// `n - 1`. Errors with [`Error::DepthExceedsTip`] if `depth` is greater than the height
+    /// of the tip.
+    pub async fn get_block_hash_at_depth(&self, depth: u32) -> Result<BlockHash, Error<T::Err>> {
+        let tip_height = self.get_tip_height().await?;
+        let height = tip_height
+            .checked_sub(depth)
+            .ok_or(Error::DepthExceedsTip { depth, tip_height })?;
+
+        self.get_block_hash(height).await
+    }
+
+    /// Get the hash of the genesis block, i.e. the block at height `0`.
+    pub async fn get_genesis_hash(&self) -> Result<BlockHash, Error<T::Err>> {
+        self.get_block_hash(0).await
+    }
+
+    /// Detect the [`Network`] of the connected instance by its genesis block hash.
+    ///
+    /// Errors with [`Error::UnknownNetwork`] if the genesis hash is not recognized.
+    pub async fn detect_network(&self) -> Result<Network, Error<T::Err>> {
+        let hash = self.get_genesis_hash().await?;
+
+        crate::network_from_genesis_hash(&hash).ok_or(Error::UnknownNetwork(hash))
+    }
+
     /// GET `/tx/:txid/hex`.
     pub async fn get_tx(&self, txid: &Txid) -> Result<Transaction, Error<T::Err>> {
         let path = format!("{}/tx/{txid}/hex", self.url);
         let body = self.get(&path).await.map_err(Error::Http)?;
-        let s = String::from_utf8_lossy(body.as_ref());
+        let s = core::str::from_utf8(body.as_ref()).map_err(Error::Utf8)?;
 
-        consensus::encode::deserialize_hex(&s).map_err(Error::DecodeHex)
+        consensus::encode::deserialize_hex(s).map_err(Error::DecodeHex)
+    }
+
+    /// GET `/tx/:txid/hex`, returning the hex string without decoding it.
+    pub async fn get_tx_hex(&self, txid: &Txid) -> Result<String, Error<T::Err>> {
+        let path = format!("{}/tx/{txid}/hex", self.url);
+        let body = self.get(&path).await.map_err(Error::Http)?;
+        let s = core::str::from_utf8(body.as_ref()).map_err(Error::Utf8)?;
+
+        Ok(s.trim().to_string())
     }
 
     /// GET `/tx/:txid`.
@@ -88,7 +567,7 @@ impl<T: Http> AsyncClient<T> {
         let path = format!("{}/tx/{txid}", self.url);
         let body = self.get(&path).await.map_err(Error::Http)?;
 
-        serde_json::from_slice(body.as_ref()).map_err(Error::Json)
+        deserialize_json(&path, body.as_ref())
     }
 
     /// GET `/tx/:txid/status`.
@@ -96,7 +575,86 @@ impl<T: Http> AsyncClient<T> {
         let path = format!("{}/tx/{txid}/status", self.url);
         let body = self.get(&path).await.map_err(Error::Http)?;
 
-        serde_json::from_slice(body.as_ref()).map_err(Error::Json)
+        deserialize_json(&path, body.as_ref())
+    }
+
+    /// GET `/tx/:txid/status`, returning `None` if the transaction is unknown, e.g. because
+    /// it hasn't propagated to the server yet.
+    pub async fn get_tx_status_opt(&self, txid: &Txid) -> Result<Option<Status>, Error<T::Err>> {
+        Self::not_found_as_none(self.get_tx_status(txid).await)
+    }
+
+    /// GET `/v1/tx/:txid/rbf`.
+    pub async fn get_rbf_info(&self, txid: &Txid) -> Result<RbfInfo, Error<T::Err>> {
+        let path = self.v1_url(&format!("/tx/{txid}/rbf"));
+        let body = self.get(&path).await.map_err(Error::Http)?;
+
+        deserialize_json(&path, body.as_ref())
+    }
+
+    /// GET `/v1/replacements`, or `/v1/fullrbf/replacements` if `full_rbf` is `true`.
+    ///
+    /// Returns the most recent replacements seen in the mempool. With `full_rbf`, only
+    /// replacements of transactions that didn't signal RBF are returned.
+    pub async fn get_recent_replacements(
+        &self,
+        full_rbf: bool,
+    ) -> Result<Vec<RbfReplacement>, Error<T::Err>> {
+        let path = if full_rbf {
+            self.v1_url("/fullrbf/replacements")
+        } else {
+            self.v1_url("/replacements")
+        };
+        let body = self.get(&path).await.map_err(Error::Http)?;
+
+        deserialize_json(&path, body.as_ref())
+    }
+
+    /// Fill in the missing [`prevout`](Vin::prevout) of each input of `tx` by fetching the
+    /// transactions it spends, making at most `concurrency` requests at a time.
+    ///
+    /// Coinbase inputs and inputs that already have a prevout are left unchanged.
+    pub async fn enrich_prevouts(
+        &self,
+        tx: &mut TxInfo,
+        concurrency: usize,
+    ) -> Result<(), Error<T::Err>> {
+        let txids: HashSet<Txid> = tx
+            .vin
+            .iter()
+            .filter(|vin| !vin.is_coinbase && vin.prevout.is_none())
+            .map(|vin| vin.txid)
+            .collect();
+        let prev_txs: HashMap<Txid, TxInfo> = stream::iter(txids)
+            .map(|txid| async move { self.get_tx_info(&txid).await.map(|tx| (txid, tx)) })
+            .buffer_unordered(concurrency.max(1))
+            .try_collect()
+            .await?;
+
+        for vin in tx.vin.iter_mut().filter(|vin| vin.prevout.is_none()) {
+            if let Some(prev_tx) = prev_txs.get(&vin.txid) {
+                vin.prevout = prev_tx.vout.get(vin.vout as usize).cloned();
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Get the [`TxLocation`] of `txid`, i.e. whether it is confirmed, in the mempool or
+    /// unknown to the server.
+    pub async fn get_tx_location(&self, txid: &Txid) -> Result<TxLocation, Error<T::Err>> {
+        let location = match self.get_tx_status_opt(txid).await? {
+            Some(Status {
+                confirmed: true,
+                block_height: Some(height),
+                block_hash: Some(hash),
+                ..
+            }) => TxLocation::Confirmed { height, hash },
+            Some(_) => TxLocation::Mempool,
+            None => TxLocation::Unknown,
+        };
+
+        Ok(location)
     }
 
     /// GET `/tx/:txid/outspend/:vout`.
@@ -111,12 +669,61 @@ impl<T: Http> AsyncClient<T> {
         Ok(self.get_outspends(txid).await?.get(vout as usize).cloned())
     }
 
+    /// GET `/tx/:txid/outspend/:vout`, without fetching all outspends.
+    ///
+    /// Note: `mempool/electrs` returns a default (unspent) output status for an output that
+    /// doesn't exist, so the result can't distinguish a non-existent output from an unspent
+    /// one. Use [`get_output_status`](Self::get_output_status) if that matters.
+    pub async fn get_outspend_raw(
+        &self,
+        txid: &Txid,
+        vout: u32,
+    ) -> Result<OutputStatus, Error<T::Err>> {
+        let path = format!("{}/tx/{txid}/outspend/{vout}", self.url);
+        let body = self.get(&path).await.map_err(Error::Http)?;
+
+        deserialize_json(&path, body.as_ref())
+    }
+
     /// GET `/tx/:txid/outspends`.
     pub async fn get_outspends(&self, txid: &Txid) -> Result<Vec<OutputStatus>, Error<T::Err>> {
         let path = format!("{}/tx/{txid}/outspends", self.url);
         let body = self.get(&path).await.map_err(Error::Http)?;
 
-        serde_json::from_slice(body.as_ref()).map_err(Error::Json)
+        deserialize_json(&path, body.as_ref())
+    }
+
+    /// Fetch the outspends of each of the given `txids`, making at most `concurrency` requests
+    /// at a time.
+    ///
+    /// The results are returned in the same order as `txids`, and a failed request doesn't
+    /// affect the others.
+    pub async fn get_outspends_batch(
+        &self,
+        txids: &[Txid],
+        concurrency: usize,
+    ) -> Vec<Result<Vec<OutputStatus>, Error<T::Err>>> {
+        stream::iter(txids)
+            .map(|txid| self.get_outspends(txid))
+            .buffered(concurrency.max(1))
+            .collect()
+            .await
+    }
+
+    /// Get the txid of the transaction spending each output of `txid`.
+    ///
+    /// Returns a `(vout, spender)` pair for every output, where `spender` is `None` if the
+    /// output is unspent.
+    pub async fn get_tx_spends(
+        &self,
+        txid: &Txid,
+    ) -> Result<Vec<(u32, Option<Txid>)>, Error<T::Err>> {
+        let outspends = self.get_outspends(txid).await?;
+
+        Ok((0..)
+            .zip(outspends)
+            .map(|(vout, status)| (vout, status.txid.filter(|_| status.spent)))
+            .collect())
     }
 
     /// GET `/scripthash/:hex/txs`.
@@ -132,7 +739,75 @@ impl<T: Http> AsyncClient<T> {
         };
         let body = self.get(&path).await.map_err(Error::Http)?;
 
-        serde_json::from_slice(body.as_ref()).map_err(Error::Json)
+        deserialize_json(&path, body.as_ref())
+    }
+
+    /// GET `/scripthash/:hex/txs/chain/:last_seen_txid`.
+    ///
+    /// Returns up to 25 confirmed transactions, newest first, after `after_txid` if given.
+    pub async fn get_scripthash_txs_chain(
+        &self,
+        script: &Script,
+        after_txid: Option<Txid>,
+    ) -> Result<Vec<AddressTx>, Error<T::Err>> {
+        let script_hash = sha256::Hash::hash(script.as_bytes());
+        let path = match after_txid {
+            Some(txid) => format!("{}/scripthash/{script_hash:x}/txs/chain/{txid}", self.url),
+            None => format!("{}/scripthash/{script_hash:x}/txs/chain", self.url),
+        };
+        let body = self.get(&path).await.map_err(Error::Http)?;
+
+        deserialize_json(&path, body.as_ref())
+    }
+
+    /// GET `/scripthash/:hex/txs/mempool`.
+    ///
+    /// Returns up to 50 unconfirmed transactions.
+    pub async fn get_scripthash_txs_mempool(
+        &self,
+        script: &Script,
+    ) -> Result<Vec<AddressTx>, Error<T::Err>> {
+        let script_hash = sha256::Hash::hash(script.as_bytes());
+        let path = format!("{}/scripthash/{script_hash:x}/txs/mempool", self.url);
+        let body = self.get(&path).await.map_err(Error::Http)?;
+
+        deserialize_json(&path, body.as_ref())
+    }
+
+    /// GET `/scripthash/:hex/utxo`.
+    pub async fn get_scripthash_utxos(
+        &self,
+        script: &Script,
+    ) -> Result<Vec<AddressUtxo>, Error<T::Err>> {
+        let script_hash = sha256::Hash::hash(script.as_bytes());
+        let path = format!("{}/scripthash/{script_hash:x}/utxo", self.url);
+        let body = self.get(&path).await.map_err(Error::Http)?;
+
+        deserialize_json(&path, body.as_ref())
+    }
+
+    /// Fetch the UTXOs of each of the given script pubkeys, making at most `concurrency`
+    /// requests at a time.
+    ///
+    /// Each script pubkey is paired with caller-provided info `K`, e.g. the derivation index
+    /// of the script, which is attached to each of its UTXOs. The UTXOs are returned in the
+    /// order of `spks`.
+    pub async fn utxos_for_spks<K: Clone>(
+        &self,
+        spks: impl IntoIterator<Item = (ScriptBuf, K)>,
+        concurrency: usize,
+    ) -> Result<Vec<(K, AddressUtxo)>, Error<T::Err>> {
+        let utxos: Vec<Vec<(K, AddressUtxo)>> = stream::iter(spks)
+            .map(|(spk, info)| async move {
+                let utxos = self.get_scripthash_utxos(&spk).await?;
+                let utxos = utxos.into_iter().map(|utxo| (info.clone(), utxo)).collect();
+                Ok::<_, Error<T::Err>>(utxos)
+            })
+            .buffered(concurrency.max(1))
+            .try_collect()
+            .await?;
+
+        Ok(utxos.into_iter().flatten().collect())
     }
 
     /// GET `/address/:address/txs`.
@@ -141,13 +816,69 @@ impl<T: Http> AsyncClient<T> {
         address: &Address,
         after_txid: Option<Txid>,
     ) -> Result<Vec<AddressTx>, Error<T::Err>> {
-        let path = match after_txid {
-            Some(txid) => format!("{}/address/{address}/txs?after_txid={txid}", self.url),
-            None => format!("{}/address/{address}/txs", self.url),
-        };
+        let path = self.address_txs_path(address, after_txid.as_ref());
         let body = self.get(&path).await.map_err(Error::Http)?;
 
-        serde_json::from_slice(body.as_ref()).map_err(Error::Json)
+        deserialize_json(&path, body.as_ref())
+    }
+
+    /// Returns the path of the page of transactions of `address` after `after_txid`.
+    ///
+    /// A [`Txid`] always displays as 64 hex characters, so it can be put in the path or
+    /// query without escaping.
+    fn address_txs_path(&self, address: &Address, after_txid: Option<&Txid>) -> String {
+        match (after_txid, self.backend) {
+            (Some(txid), Backend::MempoolSpace) => {
+                format!("{}/address/{address}/txs?after_txid={txid}", self.url)
+            }
+            (Some(txid), Backend::Esplora) => {
+                format!("{}/address/{address}/txs/chain/{txid}", self.url)
+            }
+            (None, _) => format!("{}/address/{address}/txs", self.url),
+        }
+    }
+
+    /// Get up to `limit` of the most recent transactions of `address`, newest first.
+    ///
+    /// This pages through the confirmed history with [`get_address_txs`](Self::get_address_txs)
+    /// until `limit` transactions are collected or there are no more. The first page also
+    /// includes unconfirmed transactions.
+    pub async fn get_address_txs_limited(
+        &self,
+        address: &Address,
+        limit: usize,
+    ) -> Result<Vec<AddressTx>, Error<T::Err>> {
+        let mut txs = vec![];
+        let mut after_txid = None;
+        while txs.len() < limit {
+            let page = self.get_address_txs(address, after_txid).await?;
+            let Some(last) = page.last().map(|tx| tx.txid) else {
+                break;
+            };
+            if after_txid == Some(last) {
+                break;
+            }
+            after_txid = Some(last);
+            txs.extend(page);
+        }
+        txs.truncate(limit);
+
+        Ok(txs)
+    }
+
+    /// Get all transactions of `address` as [`TxInfo`], newest first.
+    ///
+    /// `/address/:address/txs` already returns full transaction objects, so no request is
+    /// made per transaction. The history is paged through in order with
+    /// [`get_address_txs_limited`](Self::get_address_txs_limited), since each page depends on
+    /// the last txid of the previous one.
+    pub async fn get_address_txs_full(
+        &self,
+        address: &Address,
+    ) -> Result<Vec<TxInfo>, Error<T::Err>> {
+        let txs = self.get_address_txs_limited(address, usize::MAX).await?;
+
+        Ok(txs.into_iter().map(TxInfo::from).collect())
     }
 
     /// Get `address/:address/utxo`
@@ -158,7 +889,45 @@ impl<T: Http> AsyncClient<T> {
         let path = format!("{}/address/{address}/utxo", self.url);
         let body = self.get(&path).await.map_err(Error::Http)?;
 
-        serde_json::from_slice(body.as_ref()).map_err(Error::Json)
+        deserialize_json(&path, body.as_ref())
+    }
+
+    /// Returns a stream of the UTXOs of `address`.
+    ///
+    /// Neither mempool.space nor Esplora paginate `/address/:address/utxo`, so the UTXOs are
+    /// fetched in a single request and then yielded one at a time. This lets callers process
+    /// them incrementally and keeps the same interface should pagination become available.
+    pub fn address_utxos_stream<'a>(
+        &'a self,
+        address: &'a Address,
+    ) -> impl Stream<Item = Result<AddressUtxo, Error<T::Err>>> + 'a {
+        stream::once(self.get_address_utxos(address))
+            .map_ok(|utxos| stream::iter(utxos).map(Ok))
+            .try_flatten()
+    }
+
+    /// Fetch the UTXOs of `address` together with the outspends of each UTXO's transaction.
+    ///
+    /// The outspends of each distinct transaction are fetched once, concurrently.
+    pub async fn get_address_utxos_with_spends(
+        &self,
+        address: &Address,
+    ) -> Result<Vec<(AddressUtxo, Vec<OutputStatus>)>, Error<T::Err>> {
+        let utxos = self.get_address_utxos(address).await?;
+        let txids: HashSet<Txid> = utxos.iter().map(|utxo| utxo.txid).collect();
+        let outspends: HashMap<Txid, Vec<OutputStatus>> = stream::iter(txids)
+            .map(|txid| async move { self.get_outspends(&txid).await.map(|o| (txid, o)) })
+            .buffer_unordered(DEFAULT_CONCURRENCY)
+            .try_collect()
+            .await?;
+
+        Ok(utxos
+            .into_iter()
+            .map(|utxo| {
+                let spends = outspends.get(&utxo.txid).cloned().unwrap_or_default();
+                (utxo, spends)
+            })
+            .collect())
     }
 
     /// GET `/address/:address`.
@@ -166,15 +935,242 @@ impl<T: Http> AsyncClient<T> {
         let path = format!("{}/address/{address}", self.url);
         let body = self.get(&path).await.map_err(Error::Http)?;
 
-        serde_json::from_slice(body.as_ref()).map_err(Error::Json)
+        deserialize_json(&path, body.as_ref())
+    }
+
+    /// Get the [`AddressInfo`] of `address`, or `None` if the address has never been used.
+    ///
+    /// Some backends respond with `404 Not Found` for an address without history, while
+    /// others return zeroed stats. Both are reported as `None`, see [`AddressInfo::is_used`].
+    pub async fn get_address_info_opt(
+        &self,
+        address: &Address,
+    ) -> Result<Option<AddressInfo>, Error<T::Err>> {
+        let info = Self::not_found_as_none(self.get_address_info(address).await)?;
+
+        Ok(info.filter(AddressInfo::is_used))
+    }
+
+    /// Fetch the [`AddressInfo`] of each of the given `addresses`, making at most
+    /// `concurrency` requests at a time.
+    ///
+    /// The results are returned in the same order as `addresses`.
+    pub async fn get_addresses_info(
+        &self,
+        addresses: &[Address],
+        concurrency: usize,
+    ) -> Vec<Result<AddressInfo, Error<T::Err>>> {
+        stream::iter(addresses)
+            .map(|address| self.get_address_info(address))
+            .buffered(concurrency.max(1))
+            .collect()
+            .await
     }
 
     /// GET `/fees/recommended`.
+    ///
+    /// With [`Backend::Esplora`], the fees are derived from
+    /// [`get_fee_estimates`](Self::get_fee_estimates) instead.
     pub async fn get_recommended_fees(&self) -> Result<RecommendedFees, Error<T::Err>> {
-        let path = format!("{}/v1/fees/recommended", self.url);
+        if self.backend == Backend::Esplora {
+            let estimates = self.get_fee_estimates().await?;
+            return Ok(RecommendedFees::from_fee_estimates(&estimates));
+        }
+        let path = self.v1_url("/fees/recommended");
+        let body = self.get(&path).await.map_err(Error::Http)?;
+
+        deserialize_json(&path, body.as_ref())
+    }
+
+    /// GET `/fee-estimates`.
+    ///
+    /// Returns a map of confirmation target in blocks to fee rate in sat/vB.
+    pub async fn get_fee_estimates(&self) -> Result<HashMap<u16, f64>, Error<T::Err>> {
+        let path = format!("{}/fee-estimates", self.url);
+        let body = self.get(&path).await.map_err(Error::Http)?;
+
+        deserialize_json(&path, body.as_ref())
+    }
+
+    /// GET `/v1/mining/blocks/fee-rates/:time_period`.
+    ///
+    /// Returns the fee rates paid in mined blocks over `time_period`, which is the history
+    /// mempool.space keeps in place of past fee recommendations. Not available with
+    /// [`Backend::Esplora`], which responds with `404 Not Found`.
+    pub async fn get_fee_history(
+        &self,
+        time_period: TimePeriod,
+    ) -> Result<Vec<FeeSnapshot>, Error<T::Err>> {
+        let path = self.v1_url(&format!("/mining/blocks/fee-rates/{time_period}"));
+        let body = self.get(&path).await.map_err(Error::Http)?;
+
+        deserialize_json(&path, body.as_ref())
+    }
+
+    /// GET `/v1/mining/reward-stats/:block_count`.
+    ///
+    /// Returns the rewards of the last `block_count` blocks.
+    pub async fn get_reward_stats(&self, block_count: u32) -> Result<RewardStats, Error<T::Err>> {
+        let path = self.v1_url(&format!("/mining/reward-stats/{block_count}"));
+        let body = self.get(&path).await.map_err(Error::Http)?;
+
+        deserialize_json(&path, body.as_ref())
+    }
+
+    /// GET `/v1/mining/blocks/predictions/:time_period`.
+    pub async fn get_block_predictions(
+        &self,
+        time_period: TimePeriod,
+    ) -> Result<Vec<BlockPrediction>, Error<T::Err>> {
+        let path = self.v1_url(&format!("/mining/blocks/predictions/{time_period}"));
+        let body = self.get(&path).await.map_err(Error::Http)?;
+
+        deserialize_json(&path, body.as_ref())
+    }
+
+    /// GET `/v1/fees/mempool-blocks`.
+    pub async fn get_mempool_blocks(&self) -> Result<Vec<MempoolBlock>, Error<T::Err>> {
+        let path = self.v1_url("/fees/mempool-blocks");
+        let body = self.get(&path).await.map_err(Error::Http)?;
+
+        deserialize_json(&path, body.as_ref())
+    }
+
+    /// Returns the fee rate (sat/vB) at the given `percentile` of the projected mempool
+    /// blocks by virtual size.
+    ///
+    /// The percentile is counted from the highest fee rate, so `0.0` is the top of the next
+    /// block and `100.0` is the bottom of the mempool. The fee rate is read from the
+    /// [`fee_range`](MempoolBlock::fee_range) of the projected block at that position.
+    /// Returns `0.0` if the mempool is empty.
+    pub async fn recommended_fee_for_percentile(
+        &self,
+        percentile: f64,
+    ) -> Result<f64, Error<T::Err>> {
+        let blocks = self.get_mempool_blocks().await?;
+        let Some(last) = blocks.last() else {
+            return Ok(0.0);
+        };
+        let total_vsize: f64 = blocks.iter().map(|block| block.block_vsize).sum();
+        let mut ahead = total_vsize * percentile.clamp(0.0, 100.0) / 100.0;
+
+        let (block, ahead) = blocks
+            .iter()
+            .find_map(|block| {
+                if ahead <= block.block_vsize {
+                    return Some((block, ahead));
+                }
+                ahead -= block.block_vsize;
+                None
+            })
+            .unwrap_or((last, last.block_vsize));
+
+        let Some(max_index) = block.fee_range.len().checked_sub(1) else {
+            return Ok(block.median_fee);
+        };
+        let fraction = if block.block_vsize > 0.0 {
+            ahead / block.block_vsize
+        } else {
+            1.0
+        };
+        let index = ((1.0 - fraction) * max_index as f64).round() as usize;
+
+        Ok(block.fee_range[index.min(max_index)])
+    }
+
+    /// GET `/v1/mining/hashrate/pools/:timePeriod`.
+    pub async fn get_pools_hashrate(
+        &self,
+        time_period: TimePeriod,
+    ) -> Result<Vec<PoolHashratePoint>, Error<T::Err>> {
+        let path = self.v1_url(&format!("/mining/hashrate/pools/{time_period}"));
         let body = self.get(&path).await.map_err(Error::Http)?;
 
-        serde_json::from_slice(body.as_ref()).map_err(Error::Json)
+        deserialize_json(&path, body.as_ref())
+    }
+
+    /// GET `/v1/mining/pool/:slug/hashrate`.
+    pub async fn get_pool_hashrate(
+        &self,
+        slug: &str,
+    ) -> Result<Vec<PoolHashratePoint>, Error<T::Err>> {
+        let path = self.v1_url(&format!("/mining/pool/{slug}/hashrate"));
+        let body = self.get(&path).await.map_err(Error::Http)?;
+
+        deserialize_json(&path, body.as_ref())
+    }
+
+    /// GET `/v1/historical-price?currency=:currency&timestamp=:timestamp`.
+    ///
+    /// The server returns the closest price it has stored, which is hourly for recent
+    /// timestamps and daily for older ones, so [`PricePoint::time`] may differ from
+    /// `timestamp`.
+    pub async fn get_historical_price(
+        &self,
+        currency: &str,
+        timestamp: u64,
+    ) -> Result<PricePoint, Error<T::Err>> {
+        let path =
+            self.v1_url(&format!("/historical-price?currency={currency}&timestamp={timestamp}"));
+        let body = self.get(&path).await.map_err(Error::Http)?;
+        let value: serde_json::Value = deserialize_json(&path, body.as_ref())?;
+
+        let point = &value["prices"][0];
+        match (point["time"].as_u64(), point[currency].as_f64()) {
+            (Some(time), Some(price)) => Ok(PricePoint { time, price }),
+            _ => {
+                let e = serde::de::Error::custom(format!("missing {currency} price"));
+                Err(Error::json(e, &path, body.as_ref()))
+            }
+        }
+    }
+
+    /// Get the historical price in `currency` at each of the given `timestamps`.
+    ///
+    /// The API accepts a single timestamp per request, so this makes one request per
+    /// timestamp. The results are returned in the same order as `timestamps`. See
+    /// [`get_historical_price`](Self::get_historical_price) for the granularity of prices.
+    pub async fn get_historical_prices(
+        &self,
+        currency: &str,
+        timestamps: &[u64],
+    ) -> Result<Vec<PricePoint>, Error<T::Err>> {
+        stream::iter(timestamps)
+            .map(|&timestamp| self.get_historical_price(currency, timestamp))
+            .buffered(DEFAULT_CONCURRENCY)
+            .try_collect()
+            .await
+    }
+
+    /// GET `/v1/lightning/nodes/rankings/liquidity`.
+    #[cfg(feature = "lightning")]
+    pub async fn get_top_nodes_by_capacity(
+        &self,
+    ) -> Result<Vec<crate::api::LnNodeRank>, Error<T::Err>> {
+        let path = self.v1_url("/lightning/nodes/rankings/liquidity");
+        let body = self.get(&path).await.map_err(Error::Http)?;
+
+        deserialize_json(&path, body.as_ref())
+    }
+
+    /// GET `/v1/lightning/nodes/rankings/connectivity`.
+    #[cfg(feature = "lightning")]
+    pub async fn get_top_nodes_by_channels(
+        &self,
+    ) -> Result<Vec<crate::api::LnNodeRank>, Error<T::Err>> {
+        let path = self.v1_url("/lightning/nodes/rankings/connectivity");
+        let body = self.get(&path).await.map_err(Error::Http)?;
+
+        deserialize_json(&path, body.as_ref())
+    }
+
+    /// GET `/v1/lightning/nodes/rankings/age`.
+    #[cfg(feature = "lightning")]
+    pub async fn get_top_oldest_nodes(&self) -> Result<Vec<crate::api::LnNodeRank>, Error<T::Err>> {
+        let path = self.v1_url("/lightning/nodes/rankings/age");
+        let body = self.get(&path).await.map_err(Error::Http)?;
+
+        deserialize_json(&path, body.as_ref())
     }
 
     /// GET `/mempool`.
@@ -182,14 +1178,28 @@ impl<T: Http> AsyncClient<T> {
         let path = format!("{}/mempool", self.url);
         let body = self.get(&path).await.map_err(Error::Http)?;
 
-        serde_json::from_slice(body.as_ref()).map_err(Error::Json)
+        deserialize_json(&path, body.as_ref())
+    }
+
+    /// GET `/v1/mempool/stats/:time_period`.
+    ///
+    /// Only instances that keep a history of the mempool serve this endpoint. Others respond
+    /// with `404 Not Found`, which is returned as an [`Error::Http`].
+    pub async fn get_mempool_history(
+        &self,
+        time_period: TimePeriod,
+    ) -> Result<Vec<MempoolHistoryPoint>, Error<T::Err>> {
+        let path = self.v1_url(&format!("/mempool/stats/{time_period}"));
+        let body = self.get(&path).await.map_err(Error::Http)?;
+
+        deserialize_json(&path, body.as_ref())
     }
 
     /// GET `/mempool/txids`.
     pub async fn get_mempool_txids(&self) -> Result<Vec<Txid>, Error<T::Err>> {
         let path = format!("{}/mempool/txids", self.url);
         let body = self.get(&path).await.map_err(Error::Http)?;
-        let txids: Vec<String> = serde_json::from_slice(body.as_ref()).map_err(Error::Json)?;
+        let txids: Vec<String> = deserialize_json(&path, body.as_ref())?;
 
         txids
             .into_iter()
@@ -197,21 +1207,146 @@ impl<T: Http> AsyncClient<T> {
             .collect()
     }
 
+    /// Fetch the [`TxInfo`] of every transaction in the mempool, making at most `concurrency`
+    /// requests at a time.
+    ///
+    /// Transactions that can no longer be found by the time they are fetched, e.g. because
+    /// they were evicted or replaced during the scan, are skipped.
+    pub async fn hydrate_mempool(&self, concurrency: usize) -> Result<Vec<TxInfo>, Error<T::Err>> {
+        let txids = self.get_mempool_txids().await?;
+        let txs: Vec<Option<TxInfo>> = stream::iter(txids)
+            .map(|txid| async move { Self::not_found_as_none(self.get_tx_info(&txid).await) })
+            .buffered(concurrency.max(1))
+            .try_collect()
+            .await?;
+
+        Ok(txs.into_iter().flatten().collect())
+    }
+
     /// GET `/block/:hash/header`.
     pub async fn get_block_header(&self, hash: &BlockHash) -> Result<Header, Error<T::Err>> {
         let path = format!("{}/block/{hash}/header", self.url);
-        let body = self.get(&path).await.map_err(Error::Http)?;
-        let s = String::from_utf8_lossy(body.as_ref());
+        let body = self.get_cached(&path).await.map_err(Error::Http)?;
+        let s = core::str::from_utf8(body.as_ref()).map_err(Error::Utf8)?;
+
+        consensus::encode::deserialize_hex(s).map_err(Error::DecodeHex)
+    }
 
-        consensus::encode::deserialize_hex(&s).map_err(Error::DecodeHex)
+    /// Get the block header at the given `height` in the best chain.
+    ///
+    /// This resolves the block hash with [`get_block_hash`](Self::get_block_hash) and then
+    /// fetches the header with [`get_block_header`](Self::get_block_header).
+    pub async fn get_block_header_at_height(&self, height: u32) -> Result<Header, Error<T::Err>> {
+        let hash = self.get_block_hash(height).await?;
+        self.get_block_header(&hash).await
     }
 
     /// GET `/block/:hash/raw`.
     pub async fn get_block(&self, hash: &BlockHash) -> Result<Block, Error<T::Err>> {
+        let body = self.get_block_raw(hash).await?;
+
+        consensus::encode::deserialize(body.as_ref()).map_err(Error::Decode)
+    }
+
+    /// GET `/block/:hash/raw`, returning the response body without decoding it.
+    ///
+    /// This avoids holding both the raw bytes and the decoded [`Block`] in memory, e.g. to
+    /// store the block as is or to decode it incrementally with [`consensus::Decodable`].
+    pub async fn get_block_raw(&self, hash: &BlockHash) -> Result<T::Body, Error<T::Err>> {
         let path = format!("{}/block/{hash}/raw", self.url);
+
+        self.get_cached(&path).await.map_err(Error::Http)
+    }
+
+    /// GET `/block/:hash/txids`.
+    pub async fn get_block_txids(&self, hash: &BlockHash) -> Result<Vec<Txid>, Error<T::Err>> {
+        let path = format!("{}/block/{hash}/txids", self.url);
+        let body = self.get_cached(&path).await.map_err(Error::Http)?;
+
+        deserialize_json(&path, body.as_ref())
+    }
+
+    /// GET `/block/:hash`.
+    pub async fn get_block_info(&self, hash: &BlockHash) -> Result<BlockSummary, Error<T::Err>> {
+        let path = format!("{}/block/{hash}", self.url);
         let body = self.get(&path).await.map_err(Error::Http)?;
 
-        consensus::encode::deserialize(body.as_ref()).map_err(Error::Decode)
+        deserialize_json(&path, body.as_ref())
+    }
+
+    /// Get a block by either its hash or its height.
+    ///
+    /// A height is first resolved to a hash with [`get_block_hash`](Self::get_block_hash).
+    pub async fn get_block_any(&self, id: BlockId) -> Result<Block, Error<T::Err>> {
+        let hash = match id {
+            BlockId::Hash(hash) => hash,
+            BlockId::Height(height) => self.get_block_hash(height).await?,
+        };
+        self.get_block(&hash).await
+    }
+
+    /// GET `/block/:hash/txs/:start_index`.
+    ///
+    /// Returns up to 25 transactions starting at `start_index`, which must be a multiple of 25.
+    pub async fn get_block_txs(
+        &self,
+        hash: &BlockHash,
+        start_index: Option<u32>,
+    ) -> Result<Vec<TxInfo>, Error<T::Err>> {
+        let path = match start_index {
+            Some(index) => format!("{}/block/{hash}/txs/{index}", self.url),
+            None => format!("{}/block/{hash}/txs", self.url),
+        };
+        let body = self.get(&path).await.map_err(Error::Http)?;
+
+        deserialize_json(&path, body.as_ref())
+    }
+
+    /// Fetch all transactions of the block with the given `hash` in block order, requesting
+    /// at most `concurrency` pages at a time.
+    pub async fn get_block_txs_all(
+        &self,
+        hash: &BlockHash,
+        concurrency: usize,
+    ) -> Result<Vec<TxInfo>, Error<T::Err>> {
+        let tx_count = self.get_block_info(hash).await?.tx_count;
+        let pages: Vec<Vec<TxInfo>> = stream::iter((0..tx_count).step_by(BLOCK_TXS_PAGE_LEN))
+            .map(|index| self.get_block_txs(hash, Some(index)))
+            .buffered(concurrency.max(1))
+            .try_collect()
+            .await?;
+
+        Ok(pages.into_iter().flatten().collect())
+    }
+
+    /// Get the hashes of the blocks before and after the block with the given `hash`, as
+    /// `(previous, next)`.
+    ///
+    /// `previous` is `None` for the genesis block, and `next` is `None` if the block is the tip
+    /// or is not in the best chain.
+    pub async fn get_adjacent_blocks(
+        &self,
+        hash: &BlockHash,
+    ) -> Result<(Option<BlockHash>, Option<BlockHash>), Error<T::Err>> {
+        let info = self.get_block_info(hash).await?;
+        let status = self.get_block_status(hash).await?;
+
+        Ok((info.previousblockhash, status.next_best))
+    }
+
+    /// GET `/block/:hash/filter`.
+    ///
+    /// Only some Esplora forks serve BIP158 compact block filters. Others respond with
+    /// `404 Not Found`, which is returned as an [`Error::Http`].
+    #[cfg(feature = "compact-filters")]
+    pub async fn get_block_filter(
+        &self,
+        hash: &BlockHash,
+    ) -> Result<crate::api::BlockFilter, Error<T::Err>> {
+        let path = format!("{}/block/{hash}/filter", self.url);
+        let body = self.get(&path).await.map_err(Error::Http)?;
+
+        deserialize_json(&path, body.as_ref())
     }
 
     /// GET `/block/:hash/status`.
@@ -219,7 +1354,25 @@ impl<T: Http> AsyncClient<T> {
         let path = format!("{}/block/{hash}/status", self.url);
         let body = self.get(&path).await.map_err(Error::Http)?;
 
-        serde_json::from_slice(body.as_ref()).map_err(Error::Json)
+        deserialize_json(&path, body.as_ref())
+    }
+
+    /// GET `/block/:hash/status`, returning `None` if the block is unknown.
+    pub async fn get_block_status_opt(
+        &self,
+        hash: &BlockHash,
+    ) -> Result<Option<BlockStatus>, Error<T::Err>> {
+        Self::not_found_as_none(self.get_block_status(hash).await)
+    }
+
+    /// Whether the block with the given `hash` is in the best chain.
+    ///
+    /// Returns `false` for a known block that is no longer in the best chain, e.g. after a
+    /// reorg. A block the server doesn't know about results in an error rather than `false`,
+    /// so that a reorged block isn't mistaken for one that never existed. Use
+    /// [`get_block_status_opt`](Self::get_block_status_opt) to handle unknown blocks.
+    pub async fn is_block_on_best_chain(&self, hash: &BlockHash) -> Result<bool, Error<T::Err>> {
+        Ok(self.get_block_status(hash).await?.in_best_chain)
     }
 
     /// GET `/blocks/:height`.
@@ -233,30 +1386,293 @@ impl<T: Http> AsyncClient<T> {
         };
         let body = self.get(&path).await.map_err(Error::Http)?;
 
-        serde_json::from_slice(body.as_ref()).map_err(Error::Json)
+        deserialize_json(&path, body.as_ref())
+    }
+
+    /// Get the summaries of the blocks with a `timestamp` in the range `start_ts..=end_ts`, in
+    /// ascending order of height.
+    ///
+    /// This walks `/blocks/:height` backward from the tip a page at a time. Since block
+    /// timestamps aren't strictly increasing, the walk stops at the first page whose blocks
+    /// are all older than `start_ts`.
+    pub async fn get_blocks_between(
+        &self,
+        start_ts: u64,
+        end_ts: u64,
+    ) -> Result<Vec<BlockSummary>, Error<T::Err>> {
+        let mut blocks = vec![];
+        let mut height = None;
+        loop {
+            let page = self.get_blocks(height).await?;
+            let Some(lowest) = page.iter().map(|block| block.height).min() else {
+                break;
+            };
+            let done = page.iter().all(|block| block.timestamp < start_ts);
+            blocks.extend(
+                page.into_iter()
+                    .filter(|block| (start_ts..=end_ts).contains(&block.timestamp)),
+            );
+            if done || lowest == 0 {
+                break;
+            }
+            height = Some(lowest - 1);
+        }
+        blocks.sort_by_key(|block| block.height);
+
+        Ok(blocks)
+    }
+
+    /// Returns a stream of block summaries in ascending order from `start_height` up to the
+    /// current tip.
+    ///
+    /// Blocks are fetched a page at a time from `/blocks/:height`. The tip height is checked
+    /// again for every page, so blocks mined in the meantime are included and the stream ends
+    /// once it has caught up with the tip. The stream also ends after yielding an error.
+    pub fn blocks_from(
+        &self,
+        start_height: u32,
+    ) -> impl Stream<Item = Result<BlockSummary, Error<T::Err>>> + '_ {
+        let state = (start_height, VecDeque::<BlockSummary>::new(), false);
+
+        stream::unfold(state, move |(mut next, mut pending, mut done)| async move {
+            loop {
+                if let Some(block) = pending.pop_front() {
+                    return Some((Ok(block), (next, pending, done)));
+                }
+                if done {
+                    return None;
+                }
+                match self.blocks_page(next).await {
+                    Ok(blocks) => {
+                        let last = blocks.last()?.height;
+                        next = last + 1;
+                        pending.extend(blocks);
+                    }
+                    Err(e) => {
+                        done = true;
+                        return Some((Err(e), (next, pending, done)));
+                    }
+                }
+            }
+        })
+    }
+
+    /// Returns up to one page of blocks in ascending order starting at height `from`, or
+    /// an empty vec if `from` is above the tip.
+    ///
+    /// Since `/blocks/:height` returns blocks in descending order and the page size depends
+    /// on the backend, this requests the page ending at `from + 14` and keeps requesting lower
+    /// pages until it reaches `from`.
+    async fn blocks_page(&self, from: u32) -> Result<Vec<BlockSummary>, Error<T::Err>> {
+        let tip = self.get_tip_height().await?;
+        if from > tip {
+            return Ok(vec![]);
+        }
+
+        let mut height = tip.min(from.saturating_add(BLOCKS_PAGE_LEN - 1));
+        let mut blocks = vec![];
+        loop {
+            let page = self.get_blocks(Some(height)).await?;
+            let Some(lowest) = page.iter().map(|block| block.height).min() else {
+                break;
+            };
+            blocks.extend(
+                page.into_iter()
+                    .filter(|block| block.height >= from && block.height <= height),
+            );
+            if lowest <= from {
+                break;
+            }
+            height = lowest - 1;
+        }
+        blocks.sort_by_key(|block| block.height);
+
+        Ok(blocks)
+    }
+
+    /// Returns a stream of new blocks, polling the tip hash every `poll_interval`.
+    ///
+    /// The current tip is yielded first. Whenever the tip changes, the blocks connecting it
+    /// to a previously seen block are yielded in ascending order, walking back at most
+    /// `10` blocks. After a reorg the blocks of the new branch are yielded, so a height may
+    /// be yielded more than once.
+    #[cfg(feature = "tokio")]
+    pub fn new_blocks_stream(
+        &self,
+        poll_interval: core::time::Duration,
+    ) -> impl Stream<Item = Result<BlockSummary, Error<T::Err>>> + '_ {
+        let state = (VecDeque::<BlockHash>::new(), VecDeque::<BlockSummary>::new(), false);
+
+        stream::unfold(state, move |(mut seen, mut pending, mut polled)| async move {
+            loop {
+                if let Some(block) = pending.pop_front() {
+                    seen.push_back(block.id);
+                    if seen.len() > MAX_CATCH_UP_DEPTH {
+                        seen.pop_front();
+                    }
+                    return Some((Ok(block), (seen, pending, polled)));
+                }
+                if polled {
+                    tokio::time::sleep(poll_interval).await;
+                }
+                polled = true;
+                match self.blocks_since(&seen).await {
+                    Ok(blocks) => pending.extend(blocks),
+                    Err(e) => return Some((Err(e), (seen, pending, polled))),
+                }
+            }
+        })
+    }
+
+    /// Returns the blocks from the first block not in `seen` up to the current tip, in
+    /// ascending order.
+    #[cfg(feature = "tokio")]
+    async fn blocks_since(
+        &self,
+        seen: &VecDeque<BlockHash>,
+    ) -> Result<Vec<BlockSummary>, Error<T::Err>> {
+        let tip_hash = self.get_tip_hash().await?;
+        if seen.contains(&tip_hash) {
+            return Ok(vec![]);
+        }
+
+        let mut block = self.get_block_info(&tip_hash).await?;
+        let mut blocks = vec![];
+        while !seen.is_empty() && blocks.len() + 1 < MAX_CATCH_UP_DEPTH {
+            let Some(prev_hash) = block.previousblockhash else {
+                break;
+            };
+            if seen.contains(&prev_hash) {
+                break;
+            }
+            let prev = self.get_block_info(&prev_hash).await?;
+            blocks.push(block);
+            block = prev;
+        }
+        blocks.push(block);
+        blocks.reverse();
+
+        Ok(blocks)
     }
 
     /// POST `/tx`.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "debug", skip_all, fields(txid = %tx.compute_txid()), err)
+    )]
     pub async fn broadcast(&self, tx: &bitcoin::Transaction) -> Result<Txid, Error<T::Err>> {
+        self.post_tx(consensus::encode::serialize_hex(tx)).await
+    }
+
+    /// POST `/tx` with a hex-encoded transaction, e.g. as returned by an external signer.
+    ///
+    /// Errors with [`Error::HexToBytes`] without sending a request if `tx_hex` isn't valid
+    /// hex. The transaction itself is only validated by the server.
+    pub async fn broadcast_hex(&self, tx_hex: &str) -> Result<Txid, Error<T::Err>> {
+        let tx_hex = tx_hex.trim();
+        Vec::<u8>::from_hex(tx_hex).map_err(Error::HexToBytes)?;
+
+        self.post_tx(tx_hex.to_string()).await
+    }
+
+    /// Sends the hex-encoded transaction `hex` to `/tx` and parses the returned txid.
+    async fn post_tx(&self, hex: String) -> Result<Txid, Error<T::Err>> {
         let path = format!("{}/tx", self.url);
-        let hex = consensus::encode::serialize_hex(tx);
         let body = self
-            .inner
-            .send(Method::POST, &path, hex.as_bytes().to_vec())
+            .request(Method::POST, &path, hex.into_bytes())
             .await
             .map_err(Error::Http)?;
 
-        let s = String::from_utf8_lossy(body.as_ref());
+        let s = core::str::from_utf8(body.as_ref()).map_err(Error::Utf8)?;
 
         s.parse().map_err(Error::HexToArray)
     }
 
+    /// Broadcast `tx`, treating a `400 Bad Request` as a [`BroadcastOutcome`] rather than an
+    /// error.
+    ///
+    /// A transaction that is already in the mempool or in the chain is reported as
+    /// [`BroadcastOutcome::AlreadyKnown`], which makes it safe to rebroadcast. Any other
+    /// rejection is returned with the reason given by the server. Recognizing the reason
+    /// requires an [`Http`] implementation that supports [`Http::error_message`].
+    pub async fn broadcast_with_outcome(
+        &self,
+        tx: &Transaction,
+    ) -> Result<BroadcastOutcome, Error<T::Err>> {
+        match self.broadcast(tx).await {
+            Ok(txid) => Ok(BroadcastOutcome::Accepted(txid)),
+            Err(Error::Http(e)) if T::error_status(&e) == Some(400) => {
+                let reason = T::error_message(&e).unwrap_or_default();
+                if ALREADY_KNOWN_REASONS.iter().any(|r| reason.contains(r)) {
+                    Ok(BroadcastOutcome::AlreadyKnown(tx.compute_txid()))
+                } else {
+                    Ok(BroadcastOutcome::Rejected {
+                        reason: reason.to_string(),
+                    })
+                }
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Broadcast `tx` and wait until it appears in the mempool of the server.
+    ///
+    /// After broadcasting, the transaction status is polled every second. Errors with
+    /// [`Error::Timeout`] if the transaction isn't found within `timeout`.
+    #[cfg(feature = "tokio")]
+    pub async fn broadcast_and_confirm_in_mempool(
+        &self,
+        tx: &Transaction,
+        timeout: core::time::Duration,
+    ) -> Result<Txid, Error<T::Err>> {
+        let txid = self.broadcast(tx).await?;
+        let poll = async {
+            loop {
+                if self.get_tx_status_opt(&txid).await?.is_some() {
+                    return Ok(txid);
+                }
+                tokio::time::sleep(MEMPOOL_POLL_INTERVAL).await;
+            }
+        };
+
+        self.with_timeout(timeout, poll).await
+    }
+
+    /// Broadcast `tx` after checking that its consensus encoding round-trips.
+    ///
+    /// Errors with [`Error::InvalidTransaction`] without sending a request if the serialized
+    /// transaction doesn't decode to the same transaction.
+    pub async fn broadcast_checked(&self, tx: &Transaction) -> Result<Txid, Error<T::Err>> {
+        let hex = consensus::encode::serialize_hex(tx);
+        match consensus::encode::deserialize_hex::<Transaction>(&hex) {
+            Ok(decoded) if decoded == *tx => self.broadcast(tx).await,
+            _ => Err(Error::InvalidTransaction),
+        }
+    }
+
     /// GET `/tx/:txid/merkle-proof`.
     pub async fn get_merkle_proof(&self, txid: &Txid) -> Result<MerkleProof, Error<T::Err>> {
         let path = format!("{}/tx/{txid}/merkle-proof", self.url);
         let body = self.get(&path).await.map_err(Error::Http)?;
 
-        serde_json::from_slice(body.as_ref()).map_err(Error::Json)
+        deserialize_json(&path, body.as_ref())
+    }
+
+    /// Verify that the transaction `txid` is included in the best chain.
+    ///
+    /// This fetches the merkle proof for `txid`, checks that its shape matches the number of
+    /// transactions in the block at the proof's height, recomputes the merkle root, and checks
+    /// it against the `merkle_root` of the block header.
+    pub async fn verify_tx_inclusion(&self, txid: &Txid) -> Result<bool, Error<T::Err>> {
+        let proof = self.get_merkle_proof(txid).await?;
+        let hash = self.get_block_hash(proof.block_height).await?;
+        let info = self.get_block_info(&hash).await?;
+        if !proof.is_consistent_with(info.tx_count) {
+            return Ok(false);
+        }
+        let header = self.get_block_header(&hash).await?;
+
+        Ok(proof.merkle_root(txid) == header.merkle_root)
     }
 
     /// GET `/block/:hash/txid/:index`.
@@ -267,27 +1683,447 @@ impl<T: Http> AsyncClient<T> {
     ) -> Result<Txid, Error<T::Err>> {
         let path = format!("{}/block/{hash}/txid/{index}", self.url);
         let body = self.get(&path).await.map_err(Error::Http)?;
-        let s = String::from_utf8_lossy(body.as_ref());
+        let s = core::str::from_utf8(body.as_ref()).map_err(Error::Utf8)?;
 
         s.parse().map_err(Error::HexToArray)
     }
 
+    /// Get the coinbase transaction of the block with the given `hash`.
+    ///
+    /// This resolves the txid at index `0` with [`get_tx_at_index`](Self::get_tx_at_index)
+    /// and then fetches the transaction with [`get_tx_info`](Self::get_tx_info).
+    pub async fn get_coinbase_tx(&self, hash: &BlockHash) -> Result<TxInfo, Error<T::Err>> {
+        let txid = self.get_tx_at_index(hash, 0).await?;
+        self.get_tx_info(&txid).await
+    }
+
+    /// Get the reward of the block with the given `hash`, split into the subsidy and fees.
+    ///
+    /// The total is the value of the outputs of the coinbase transaction, and the subsidy is
+    /// computed from the height of the block. A miner may claim less than the full reward,
+    /// in which case the fees are reduced accordingly.
+    pub async fn get_block_reward(&self, hash: &BlockHash) -> Result<BlockReward, Error<T::Err>> {
+        let coinbase = self.get_coinbase_tx(hash).await?;
+        let height = match coinbase.status.block_height {
+            Some(height) => height,
+            None => self.get_block_info(hash).await?.height,
+        };
+        let total = coinbase.output_value();
+        let subsidy = crate::util::block_subsidy(height);
+
+        Ok(BlockReward {
+            subsidy,
+            fees: total.saturating_sub(subsidy),
+            total,
+        })
+    }
+
     /// GET `/tx/:txid/merkleblock-proof`.
     pub async fn get_merkle_block(&self, txid: &Txid) -> Result<MerkleBlock, Error<T::Err>> {
         let path = format!("{}/tx/{txid}/merkleblock-proof", self.url);
         let body = self.get(&path).await.map_err(Error::Http)?;
-        let s = String::from_utf8_lossy(body.as_ref());
+        let s = core::str::from_utf8(body.as_ref()).map_err(Error::Utf8)?;
+
+        consensus::encode::deserialize_hex(s).map_err(Error::DecodeHex)
+    }
+
+    /// Get the hash of the block containing `txid` and the index of the transaction in the
+    /// block, using the [`MerkleBlock`] from [`get_merkle_block`](Self::get_merkle_block).
+    ///
+    /// Errors with [`Error::MerkleBlock`] if the merkle block doesn't commit to the merkle
+    /// root of its header, and with [`Error::TxNotMatched`] if it doesn't match `txid`.
+    pub async fn get_merkle_block_matches(
+        &self,
+        txid: &Txid,
+    ) -> Result<(BlockHash, usize), Error<T::Err>> {
+        let merkle_block = self.get_merkle_block(txid).await?;
+        let mut matches = vec![];
+        let mut indexes = vec![];
+        merkle_block
+            .extract_matches(&mut matches, &mut indexes)
+            .map_err(Error::MerkleBlock)?;
+
+        let index = matches
+            .iter()
+            .position(|m| m == txid)
+            .ok_or(Error::TxNotMatched(*txid))?;
+
+        Ok((merkle_block.header.block_hash(), indexes[index] as usize))
+    }
+}
+
+/// Builder struct for [`AsyncClient`].
+#[derive(Debug)]
+pub struct AsyncClientBuilder<T> {
+    /// The client being built.
+    inner: AsyncClient<T>,
+}
+
+impl<T: Http> AsyncClientBuilder<T> {
+    /// Set the base URL of the API. A trailing `/` is removed.
+    pub fn url(mut self, url: &str) -> Self {
+        self.inner.url = url.trim_end_matches('/').to_string();
+        self
+    }
 
-        consensus::encode::deserialize_hex(&s).map_err(Error::DecodeHex)
+    /// Set the base URL to the mempool.space API of the given `network`, see
+    /// [`network_url`](crate::network_url).
+    ///
+    /// The network is also used to parse addresses, see [`AsyncClient::with_network`]. The URL
+    /// isn't changed for [`Network::Regtest`], which has no public instance. Use
+    /// [`url`](Self::url) to set it.
+    pub fn network(mut self, network: Network) -> Self {
+        self.inner.network = Some(network);
+        match crate::network_url(network) {
+            Some(url) => self.url(url),
+            None => self,
+        }
+    }
+
+    /// Set the fallback base URLs, see [`AsyncClient::with_fallbacks`].
+    pub fn fallbacks(mut self, urls: &[&str]) -> Self {
+        self.inner.fallbacks =
+            urls.iter().map(|url| url.trim_end_matches('/').to_string()).collect();
+        self
+    }
+
+    /// Set whether fee, mining and price endpoints are prefixed with `/v1`, see
+    /// [`AsyncClient::with_v1_prefix`].
+    pub fn v1_prefix(mut self, v1_prefix: bool) -> Self {
+        self.inner.v1_prefix = v1_prefix;
+        self
+    }
+
+    /// Set the kind of [`Backend`] serving the API, see [`AsyncClient::with_backend`].
+    pub fn backend(mut self, backend: Backend) -> Self {
+        self.inner = self.inner.with_backend(backend);
+        self
+    }
+
+    /// Set a hook that rewrites the URL of each request, see
+    /// [`AsyncClient::with_request_hook`].
+    pub fn request_hook(
+        mut self,
+        hook: impl Fn(&Method, &str) -> String + Send + Sync + 'static,
+    ) -> Self {
+        self.inner.request_hook = Some(Arc::new(hook));
+        self
+    }
+
+    /// Set a recorder of successful responses, see [`AsyncClient::with_response_recorder`].
+    pub fn response_recorder(
+        mut self,
+        recorder: impl Fn(&str, &[u8]) + Send + Sync + 'static,
+    ) -> Self {
+        self.inner.response_recorder = Some(Arc::new(recorder));
+        self
+    }
+
+    /// Add a header that is sent with each request, see [`AsyncClient::with_header`].
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        self.inner.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    /// Enable caching of immutable block data, see [`AsyncClient::with_etag_cache`].
+    pub fn etag_cache(mut self, capacity: usize) -> Self {
+        self.inner.etag_cache = Some(Arc::new(EtagCache::new(capacity)));
+        self
     }
+
+    /// Returns the [`AsyncClient`].
+    pub fn build(self) -> AsyncClient<T> {
+        self.inner
+    }
+}
+
+/// Deserializes the JSON `body` of a response from `path`.
+fn deserialize_json<D: DeserializeOwned, E>(path: &str, body: &[u8]) -> Result<D, Error<E>> {
+    serde_json::from_slice(body).map_err(|e| Error::json(e, path, body))
 }
 
 #[cfg(test)]
 mod test {
+    use std::sync::Mutex;
+
+    use bitcoin::address::NetworkUnchecked;
+    use bitcoin::{absolute, transaction};
+
     use super::*;
 
     const URL: &str = "https://mempool.space/api";
 
+    /// Mock HTTP client that records the requested URLs and responds with a fixed body, or
+    /// with an error status for URLs with a failing prefix.
+    #[derive(Debug)]
+    struct MockHttp {
+        response: Vec<u8>,
+        errors: Vec<(String, u16)>,
+        urls: Mutex<Vec<String>>,
+        etags: Mutex<Vec<Option<String>>>,
+        headers: Mutex<Vec<String>>,
+    }
+
+    impl MockHttp {
+        /// `ETag` of the response to a conditional request.
+        const ETAG: &str = "\"mock\"";
+
+        fn new(response: impl Into<Vec<u8>>) -> Self {
+            Self {
+                response: response.into(),
+                errors: vec![],
+                urls: Mutex::new(vec![]),
+                etags: Mutex::new(vec![]),
+                headers: Mutex::new(vec![]),
+            }
+        }
+
+        /// Respond to URLs starting with `prefix` with the error `status`.
+        fn with_error(mut self, prefix: &str, status: u16) -> Self {
+            self.errors.push((prefix.to_string(), status));
+            self
+        }
+
+        fn urls(&self) -> Vec<String> {
+            self.urls.lock().unwrap().clone()
+        }
+
+        /// Records a request to `url` and returns its error status, if any.
+        fn record(&self, url: &str) -> Result<(), u16> {
+            self.urls.lock().unwrap().push(url.to_string());
+            match self.errors.iter().find(|(prefix, _)| url.starts_with(prefix)) {
+                Some(&(_, status)) => Err(status),
+                None => Ok(()),
+            }
+        }
+    }
+
+    impl Http for MockHttp {
+        type Body = Vec<u8>;
+
+        /// The HTTP status of the error response.
+        type Err = u16;
+
+        async fn send<'a>(
+            &'a self,
+            _method: Method,
+            url: &'a str,
+            _body: impl Into<Self::Body>,
+        ) -> Result<Self::Body, Self::Err>
+        where
+            Self: 'a,
+        {
+            self.record(url)?;
+            Ok(self.response.clone())
+        }
+
+        async fn send_with_headers<'a>(
+            &'a self,
+            method: Method,
+            url: &'a str,
+            headers: &'a [(&'a str, &'a str)],
+            body: impl Into<Self::Body>,
+        ) -> Result<Self::Body, Self::Err>
+        where
+            Self: 'a,
+        {
+            let headers = headers.iter().map(|(name, value)| format!("{name}: {value}"));
+            self.headers.lock().unwrap().extend(headers);
+            self.send(method, url, body).await
+        }
+
+        async fn get_conditional<'a>(
+            &'a self,
+            url: &'a str,
+            etag: Option<&'a str>,
+        ) -> Result<Conditional<Self::Body>, Self::Err>
+        where
+            Self: 'a,
+        {
+            self.record(url)?;
+            self.etags.lock().unwrap().push(etag.map(str::to_string));
+            if etag == Some(Self::ETAG) {
+                return Ok(Conditional::NotModified);
+            }
+            Ok(Conditional::Modified {
+                body: self.response.clone(),
+                etag: Some(Self::ETAG.to_string()),
+            })
+        }
+
+        fn error_status(err: &Self::Err) -> Option<u16> {
+            Some(*err)
+        }
+    }
+
+    #[test]
+    fn test_address_txs_path() {
+        let address: Address = "bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq"
+            .parse::<Address<NetworkUnchecked>>()
+            .unwrap()
+            .assume_checked();
+        let txid: Txid = "4a5e1e4baab89f3a32518a88c31bc87f618f76673e2cc77ab2127b7afdeda33b"
+            .parse()
+            .unwrap();
+        let client = AsyncClient::new(URL, MockHttp::new(""));
+
+        assert_eq!(
+            client.address_txs_path(&address, None),
+            format!("{URL}/address/{address}/txs"),
+        );
+        let path = client.address_txs_path(&address, Some(&txid));
+        let (prefix, after_txid) = path.split_once("?after_txid=").unwrap();
+        assert_eq!(prefix, format!("{URL}/address/{address}/txs"));
+        assert_eq!(after_txid.len(), 64);
+        assert!(after_txid.chars().all(|c| c.is_ascii_hexdigit()));
+        assert_eq!(after_txid, txid.to_string());
+    }
+
+    #[test]
+    fn test_builder_network() {
+        let client = AsyncClient::builder(MockHttp::new(""))
+            .url("http://localhost:3002/api")
+            .build();
+        assert_eq!(client.network(), None);
+
+        let client = AsyncClient::builder(MockHttp::new(""))
+            .network(Network::Regtest)
+            .url("http://localhost:3002/api")
+            .build();
+        assert_eq!(client.network(), Some(Network::Regtest));
+    }
+
+    #[tokio::test]
+    async fn test_with_inner() -> anyhow::Result<()> {
+        let client = AsyncClient::builder(MockHttp::new(""))
+            .network(Network::Signet)
+            .request_hook(|_, url| url.replace("mempool.space", "mirror.example"))
+            .build();
+        let http = MockHttp::new("100");
+        let copy = client.with_inner(&http);
+        assert_eq!(copy.network(), Some(Network::Signet));
+        assert_eq!(copy.base_url(), client.base_url());
+
+        assert_eq!(copy.get_tip_height().await?, 100);
+        assert_eq!(http.urls(), ["https://mirror.example/signet/api/blocks/tip/height"]);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_recommended_fee_for_percentile() -> anyhow::Result<()> {
+        // GET /v1/fees/mempool-blocks
+        let json = r#"[
+            {"blockSize":1612463,"blockVSize":997961.25,"nTx":3105,"totalFees":5489233,"medianFee":4.5,"feeRange":[2.0,3.0,5.0,10.0,50.0]},
+            {"blockSize":803117,"blockVSize":500000.75,"nTx":1423,"totalFees":750004,"medianFee":1.5,"feeRange":[1.0,1.5,2.0]}
+        ]"#;
+        let http = MockHttp::new(json);
+        let client = AsyncClient::new(URL, &http);
+
+        let blocks = client.get_mempool_blocks().await?;
+        assert_eq!(blocks[1].block_vsize, 500_000.75);
+
+        assert_eq!(client.recommended_fee_for_percentile(0.0).await?, 50.0);
+        assert_eq!(client.recommended_fee_for_percentile(50.0).await?, 3.0);
+        assert_eq!(client.recommended_fee_for_percentile(100.0).await?, 1.0);
+        assert!(
+            http.urls()
+                .iter()
+                .all(|url| url == &format!("{URL}/v1/fees/mempool-blocks"))
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_etag_cache_not_modified() -> anyhow::Result<()> {
+        let hash = BlockHash::all_zeros();
+        let txids = vec![Txid::all_zeros()];
+        let http = MockHttp::new(serde_json::to_vec(&txids)?);
+        let client = AsyncClient::new(URL, &http).with_etag_cache(8);
+
+        assert_eq!(client.get_block_txids(&hash).await?, txids);
+        assert_eq!(client.get_block_txids(&hash).await?, txids);
+        assert_eq!(*http.etags.lock().unwrap(), [None, Some(MockHttp::ETAG.to_string())]);
+
+        let debug = format!("{client:?}");
+        assert!(debug.contains("EtagCache { capacity: 8, len: 1 }"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_headers() -> anyhow::Result<()> {
+        let http = MockHttp::new("[]");
+        let client = AsyncClient::builder(&http)
+            .header("X-Api-Key", "secret")
+            .etag_cache(8)
+            .build();
+        client.get_block_txids(&BlockHash::all_zeros()).await?;
+
+        assert_eq!(*http.headers.lock().unwrap(), ["X-Api-Key: secret"]);
+        assert!(http.etags.lock().unwrap().is_empty());
+        assert!(format!("{client:?}").contains(r#"headers: ["X-Api-Key"]"#));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_fallbacks() -> anyhow::Result<()> {
+        let urls = ["https://a.example/api", "https://b.example/api"];
+
+        let http = MockHttp::new("100").with_error(urls[0], 503);
+        let client = AsyncClient::with_fallbacks(&urls, &http).with_etag_cache(8);
+        assert_eq!(client.get_tip_height().await?, 100);
+        assert_eq!(
+            http.urls(),
+            [
+                format!("{}/blocks/tip/height", urls[0]),
+                format!("{}/blocks/tip/height", urls[1])
+            ]
+        );
+
+        // Conditional requests of the ETag cache fail over too.
+        let hash = BlockHash::all_zeros();
+        let http = MockHttp::new("[]").with_error(urls[0], 503);
+        let client = AsyncClient::with_fallbacks(&urls, &http).with_etag_cache(8);
+        assert!(client.get_block_txids(&hash).await?.is_empty());
+        assert_eq!(
+            http.urls(),
+            [
+                format!("{}/block/{hash}/txids", urls[0]),
+                format!("{}/block/{hash}/txids", urls[1])
+            ]
+        );
+
+        let http = MockHttp::new("100").with_error(urls[0], 404);
+        let client = AsyncClient::with_fallbacks(&urls, &http);
+        assert!(matches!(client.get_tip_height().await, Err(Error::Http(404))));
+        assert_eq!(http.urls(), [format!("{}/blocks/tip/height", urls[0])]);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_broadcast_trailing_slash() -> anyhow::Result<()> {
+        let tx = Transaction {
+            version: transaction::Version::TWO,
+            lock_time: absolute::LockTime::ZERO,
+            input: vec![],
+            output: vec![],
+        };
+        let txid = tx.compute_txid();
+
+        for url in [URL, "https://mempool.space/api/"] {
+            let http = MockHttp::new(txid.to_string());
+            let client = AsyncClient::new(url, &http);
+            assert_eq!(client.broadcast(&tx).await?, txid);
+            assert_eq!(http.urls(), ["https://mempool.space/api/tx"]);
+        }
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_get_tip() -> anyhow::Result<()> {
         let bitreq_client = crate::BitreqClient::new();
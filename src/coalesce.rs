@@ -0,0 +1,254 @@
+//! [`Coalesce`].
+
+use core::fmt::{self, Debug};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use futures::channel::oneshot;
+
+use crate::{Conditional, Http, HttpMethod};
+
+/// Result shared by the callers of a coalesced request.
+type SharedResult<H> = Result<<H as Http>::Body, Arc<<H as Http>::Err>>;
+
+/// Map of URL to the callers waiting on an in-flight request.
+type Waiters<H> = HashMap<String, Vec<oneshot::Sender<SharedResult<H>>>>;
+
+/// [`Http`] wrapper that coalesces concurrent identical GET requests.
+///
+/// While a GET request to a URL is in flight, further GET requests to the same URL wait for
+/// and share its result instead of sending a request of their own. Other requests are
+/// forwarded as is. Since an error may be shared by several callers, errors are wrapped
+/// in an [`Arc`].
+pub struct Coalesce<H: Http> {
+    /// inner HTTP client.
+    inner: H,
+    /// Callers waiting on each in-flight request.
+    waiters: Mutex<Waiters<H>>,
+}
+
+impl<H: Http + Debug> Debug for Coalesce<H> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Coalesce")
+            .field("inner", &self.inner)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<H: Http> Coalesce<H> {
+    /// New.
+    pub fn new(inner: H) -> Self {
+        Self {
+            inner,
+            waiters: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Locks the map of waiters.
+    fn waiters(&self) -> MutexGuard<'_, Waiters<H>> {
+        self.waiters.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl<H> Http for Coalesce<H>
+where
+    H: Http,
+    H::Body: Clone,
+{
+    type Body = H::Body;
+
+    type Err = Arc<H::Err>;
+
+    async fn send<'a>(
+        &'a self,
+        method: HttpMethod,
+        url: &'a str,
+        body: impl Into<Self::Body>,
+    ) -> Result<Self::Body, Self::Err>
+    where
+        Self: 'a,
+    {
+        if method != HttpMethod::GET {
+            return self.inner.send(method, url, body).await.map_err(Arc::new);
+        }
+
+        let rx = {
+            let mut waiters = self.waiters();
+            match waiters.get_mut(url) {
+                Some(senders) => {
+                    let (tx, rx) = oneshot::channel();
+                    senders.push(tx);
+                    Some(rx)
+                }
+                None => {
+                    waiters.insert(url.to_string(), vec![]);
+                    None
+                }
+            }
+        };
+
+        match rx {
+            Some(rx) => match rx.await {
+                Ok(res) => res,
+                // The leading request was dropped before completing.
+                Err(oneshot::Canceled) => {
+                    self.inner.send(method, url, body).await.map_err(Arc::new)
+                }
+            },
+            None => {
+                let in_flight = InFlight {
+                    coalesce: self,
+                    url,
+                    completed: false,
+                };
+                let res = self.inner.send(method, url, body).await.map_err(Arc::new);
+                in_flight.complete(&res);
+                res
+            }
+        }
+    }
+
+    async fn send_with_headers<'a>(
+        &'a self,
+        method: HttpMethod,
+        url: &'a str,
+        headers: &'a [(&'a str, &'a str)],
+        body: impl Into<Self::Body>,
+    ) -> Result<Self::Body, Self::Err>
+    where
+        Self: 'a,
+    {
+        // Responses may depend on the headers, so these requests aren't coalesced.
+        self.inner
+            .send_with_headers(method, url, headers, body)
+            .await
+            .map_err(Arc::new)
+    }
+
+    async fn get_conditional<'a>(
+        &'a self,
+        url: &'a str,
+        etag: Option<&'a str>,
+    ) -> Result<Conditional<Self::Body>, Self::Err>
+    where
+        Self: 'a,
+    {
+        self.inner.get_conditional(url, etag).await.map_err(Arc::new)
+    }
+
+    fn error_status(err: &Self::Err) -> Option<u16> {
+        H::error_status(err)
+    }
+
+    fn error_message(err: &Self::Err) -> Option<&str> {
+        H::error_message(err)
+    }
+}
+
+/// Guard for a leading request, which removes its entry from the map of waiters when dropped.
+struct InFlight<'a, H: Http> {
+    /// The coalescing client.
+    coalesce: &'a Coalesce<H>,
+    /// The request URL.
+    url: &'a str,
+    /// Whether the waiters have been notified.
+    completed: bool,
+}
+
+impl<H> InFlight<'_, H>
+where
+    H: Http,
+    H::Body: Clone,
+{
+    /// Sends the result of the request to all waiters.
+    fn complete(mut self, res: &SharedResult<H>) {
+        self.completed = true;
+        let senders = self.coalesce.waiters().remove(self.url).unwrap_or_default();
+        for tx in senders {
+            let _ = tx.send(res.clone());
+        }
+    }
+}
+
+impl<H: Http> Drop for InFlight<'_, H> {
+    fn drop(&mut self) {
+        // Dropping the senders of an incomplete request notifies the waiters.
+        if !self.completed {
+            self.coalesce.waiters().remove(self.url);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use core::convert::Infallible;
+    use core::sync::atomic::{AtomicUsize, Ordering};
+    use core::time::Duration;
+
+    use futures::future;
+
+    use super::*;
+
+    const URL: &str = "https://mempool.space/api/blocks/tip/height";
+
+    /// Mock HTTP client that counts the requests it sends and responds after a short delay.
+    #[derive(Debug, Default)]
+    struct CountingHttp {
+        count: AtomicUsize,
+    }
+
+    impl Http for CountingHttp {
+        type Body = Vec<u8>;
+
+        type Err = Infallible;
+
+        async fn send<'a>(
+            &'a self,
+            _method: HttpMethod,
+            _url: &'a str,
+            _body: impl Into<Self::Body>,
+        ) -> Result<Self::Body, Self::Err>
+        where
+            Self: 'a,
+        {
+            self.count.fetch_add(1, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            Ok(b"100".to_vec())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_coalesce_concurrent_gets() {
+        let http = Coalesce::new(CountingHttp::default());
+        let requests = (0..5).map(|_| http.send(HttpMethod::GET, URL, vec![]));
+
+        for res in future::join_all(requests).await {
+            assert_eq!(res.unwrap(), b"100");
+        }
+        assert_eq!(http.inner.count.load(Ordering::SeqCst), 1);
+        assert!(http.waiters().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_coalesce_leader_dropped() {
+        let http = Coalesce::new(CountingHttp::default());
+
+        let mut leader = Box::pin(http.send(HttpMethod::GET, URL, vec![]));
+        assert!(futures::poll!(&mut leader).is_pending());
+        let mut waiters: Vec<_> = (0..2)
+            .map(|_| Box::pin(http.send(HttpMethod::GET, URL, vec![])))
+            .collect();
+        for waiter in &mut waiters {
+            assert!(futures::poll!(waiter).is_pending());
+        }
+        assert_eq!(http.waiters()[URL].len(), 2);
+
+        drop(leader);
+        for res in future::join_all(waiters).await {
+            assert_eq!(res.unwrap(), b"100");
+        }
+        // Each waiter sends the request itself once the leader is gone.
+        assert_eq!(http.inner.count.load(Ordering::SeqCst), 3);
+        assert!(http.waiters().is_empty());
+    }
+}
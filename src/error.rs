@@ -1,8 +1,10 @@
 //! [`Error`].
 
 use core::num::ParseIntError;
+use core::str::Utf8Error;
 
-use bitcoin::{consensus, hex};
+use bitcoin::merkle_tree::MerkleBlockError;
+use bitcoin::{BlockHash, Txid, consensus, hex};
 
 /// Errors that can occur in this library.
 #[derive(Debug)]
@@ -13,12 +15,93 @@ pub enum Error<E> {
     DecodeHex(consensus::encode::FromHexError),
     /// Converting from hex to array
     HexToArray(hex::HexToArrayError),
-    /// `serde_json` error.
-    Json(serde_json::Error),
+    /// Converting from hex to bytes
+    HexToBytes(hex::HexToBytesError),
+    /// `serde_json` error, with the request path and the beginning of the response body.
+    Json {
+        /// The `serde_json` error.
+        source: serde_json::Error,
+        /// The path of the request.
+        path: String,
+        /// The first bytes of the response body.
+        snippet: String,
+    },
     /// Error parsing an integer.
     ParseInt(ParseIntError),
+    /// The response body is not valid UTF-8.
+    Utf8(Utf8Error),
     /// Http error.
     Http(E),
+    /// The genesis block hash doesn't match any known network.
+    UnknownNetwork(BlockHash),
+    /// The transaction doesn't round-trip through its consensus encoding.
+    InvalidTransaction,
+    /// The operation didn't complete in time.
+    Timeout,
+    /// The operation was cancelled.
+    Cancelled,
+    /// The merkle block is invalid.
+    MerkleBlock(MerkleBlockError),
+    /// The transaction isn't matched by the merkle block.
+    TxNotMatched(Txid),
+    /// The requested depth is greater than the height of the tip.
+    DepthExceedsTip {
+        /// The requested depth.
+        depth: u32,
+        /// The height of the tip.
+        tip_height: u32,
+    },
+}
+
+/// Maximum length of the response body snippet in [`Error::Json`].
+const SNIPPET_LEN: usize = 200;
+
+impl<E> Error<E> {
+    /// Creates an [`Error::Json`] from a `source` error and the `body` of a response
+    /// from `path`.
+    pub(crate) fn json(source: serde_json::Error, path: &str, body: &[u8]) -> Self {
+        let snippet = &body[..body.len().min(SNIPPET_LEN)];
+        Self::Json {
+            source,
+            path: path.to_string(),
+            snippet: String::from_utf8_lossy(snippet).into_owned(),
+        }
+    }
+
+    /// Converts the transport error to a boxed trait object, so that errors of clients using
+    /// different [`Http`](crate::Http) implementations have the same type.
+    pub fn boxed(self) -> Error<Box<dyn std::error::Error + Send + Sync>>
+    where
+        E: std::error::Error + Send + Sync + 'static,
+    {
+        match self {
+            Self::Decode(e) => Error::Decode(e),
+            Self::DecodeHex(e) => Error::DecodeHex(e),
+            Self::HexToArray(e) => Error::HexToArray(e),
+            Self::HexToBytes(e) => Error::HexToBytes(e),
+            Self::Json {
+                source,
+                path,
+                snippet,
+            } => Error::Json {
+                source,
+                path,
+                snippet,
+            },
+            Self::ParseInt(e) => Error::ParseInt(e),
+            Self::Utf8(e) => Error::Utf8(e),
+            Self::Http(e) => Error::Http(Box::new(e)),
+            Self::UnknownNetwork(hash) => Error::UnknownNetwork(hash),
+            Self::InvalidTransaction => Error::InvalidTransaction,
+            Self::Timeout => Error::Timeout,
+            Self::Cancelled => Error::Cancelled,
+            Self::MerkleBlock(e) => Error::MerkleBlock(e),
+            Self::TxNotMatched(txid) => Error::TxNotMatched(txid),
+            Self::DepthExceedsTip { depth, tip_height } => {
+                Error::DepthExceedsTip { depth, tip_height }
+            }
+        }
+    }
 }
 
 impl<E: core::fmt::Display> core::fmt::Display for Error<E> {
@@ -27,9 +110,24 @@ impl<E: core::fmt::Display> core::fmt::Display for Error<E> {
             Self::Decode(e) => write!(f, "{e}"),
             Self::DecodeHex(e) => write!(f, "{e}"),
             Self::HexToArray(e) => write!(f, "{e}"),
-            Self::Json(e) => write!(f, "{e}"),
+            Self::HexToBytes(e) => write!(f, "{e}"),
+            Self::Json {
+                source,
+                path,
+                snippet,
+            } => write!(f, "{source} in response from {path}: {snippet}"),
             Self::ParseInt(e) => write!(f, "{e}"),
+            Self::Utf8(e) => write!(f, "{e}"),
             Self::Http(e) => write!(f, "{e}"),
+            Self::UnknownNetwork(hash) => write!(f, "unknown network with genesis hash {hash}"),
+            Self::InvalidTransaction => write!(f, "transaction failed to round-trip encoding"),
+            Self::Timeout => write!(f, "operation timed out"),
+            Self::Cancelled => write!(f, "operation cancelled"),
+            Self::MerkleBlock(e) => write!(f, "{e}"),
+            Self::TxNotMatched(txid) => write!(f, "transaction {txid} not matched by merkle block"),
+            Self::DepthExceedsTip { depth, tip_height } => {
+                write!(f, "depth {depth} exceeds tip height {tip_height}")
+            }
         }
     }
 }
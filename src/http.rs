@@ -6,6 +6,8 @@ use core::ops::Deref;
 enum Method {
     Get,
     Post,
+    Put,
+    Delete,
 }
 
 /// HTTP method.
@@ -17,6 +19,10 @@ impl HttpMethod {
     pub const GET: Self = Self(Method::Get);
     /// POST.
     pub const POST: Self = Self(Method::Post);
+    /// PUT.
+    pub const PUT: Self = Self(Method::Put);
+    /// DELETE.
+    pub const DELETE: Self = Self(Method::Delete);
 }
 
 #[cfg(feature = "bitreq")]
@@ -25,10 +31,26 @@ impl From<HttpMethod> for bitreq::Method {
         match method.0 {
             Method::Get => bitreq::Method::Get,
             Method::Post => bitreq::Method::Post,
+            Method::Put => bitreq::Method::Put,
+            Method::Delete => bitreq::Method::Delete,
         }
     }
 }
 
+/// Response to a conditional GET request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Conditional<B> {
+    /// The resource was returned, along with its `ETag` if any.
+    Modified {
+        /// Response body.
+        body: B,
+        /// Value of the `ETag` response header.
+        etag: Option<String>,
+    },
+    /// `304 Not Modified`: a previously returned body is still valid.
+    NotModified,
+}
+
 /// Trait describing the behavior required of the HTTP client.
 pub trait Http {
     /// Body
@@ -46,6 +68,58 @@ pub trait Http {
     ) -> impl Future<Output = Result<Self::Body, Self::Err>>
     where
         Self: 'a;
+
+    /// Send a request to a `url` with the given `headers` and return a future response body.
+    ///
+    /// The default implementation ignores `headers` and calls [`send`](Self::send).
+    fn send_with_headers<'a>(
+        &'a self,
+        method: HttpMethod,
+        url: &'a str,
+        headers: &'a [(&'a str, &'a str)],
+        body: impl Into<Self::Body>,
+    ) -> impl Future<Output = Result<Self::Body, Self::Err>>
+    where
+        Self: 'a,
+    {
+        let _ = headers;
+        self.send(method, url, body)
+    }
+
+    /// Send a GET request to `url` with an `If-None-Match` header set to `etag`, if any.
+    ///
+    /// The default implementation ignores `etag` and always returns
+    /// [`Conditional::Modified`] without an `ETag`, which disables caching.
+    fn get_conditional<'a>(
+        &'a self,
+        url: &'a str,
+        etag: Option<&'a str>,
+    ) -> impl Future<Output = Result<Conditional<Self::Body>, Self::Err>>
+    where
+        Self: 'a,
+    {
+        let _ = etag;
+        async move {
+            let body = self.send(HttpMethod::GET, url, Vec::new()).await?;
+            Ok(Conditional::Modified { body, etag: None })
+        }
+    }
+
+    /// Returns the HTTP status code of the response that caused `err`, if any.
+    ///
+    /// This allows the client to recognize responses such as `404 Not Found`. The default
+    /// implementation returns `None`.
+    fn error_status(_err: &Self::Err) -> Option<u16> {
+        None
+    }
+
+    /// Returns the body text of the error response that caused `err`, if any.
+    ///
+    /// This allows the client to recognize the reason a request was rejected, such as a
+    /// failed broadcast. The default implementation returns `None`.
+    fn error_message(_err: &Self::Err) -> Option<&str> {
+        None
+    }
 }
 
 impl<T> Http for T
@@ -68,4 +142,36 @@ where
     {
         (**self).send(method, url, body)
     }
+
+    fn send_with_headers<'a>(
+        &'a self,
+        method: HttpMethod,
+        url: &'a str,
+        headers: &'a [(&'a str, &'a str)],
+        body: impl Into<Self::Body>,
+    ) -> impl Future<Output = Result<Self::Body, Self::Err>>
+    where
+        Self: 'a,
+    {
+        (**self).send_with_headers(method, url, headers, body)
+    }
+
+    fn get_conditional<'a>(
+        &'a self,
+        url: &'a str,
+        etag: Option<&'a str>,
+    ) -> impl Future<Output = Result<Conditional<Self::Body>, Self::Err>>
+    where
+        Self: 'a,
+    {
+        (**self).get_conditional(url, etag)
+    }
+
+    fn error_status(err: &Self::Err) -> Option<u16> {
+        <T::Target as Http>::error_status(err)
+    }
+
+    fn error_message(err: &Self::Err) -> Option<&str> {
+        <T::Target as Http>::error_message(err)
+    }
 }
@@ -4,12 +4,20 @@
 pub mod api;
 #[cfg(feature = "bitreq")]
 mod bitreq_client;
+mod cache;
 mod client;
+mod coalesce;
 mod error;
 mod http;
+mod network;
+mod retry;
+pub mod util;
 
 #[cfg(feature = "bitreq")]
 pub use bitreq_client::*;
 pub use client::*;
+pub use coalesce::*;
 pub use error::*;
 pub use http::*;
+pub use network::*;
+pub use retry::*;
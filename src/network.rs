@@ -0,0 +1,44 @@
+//! [`Network`] detection and URL presets.
+
+use bitcoin::{BlockHash, Network, constants::genesis_block};
+
+/// URL of the mempool.space API for mainnet.
+pub const MAINNET_URL: &str = "https://mempool.space/api";
+/// URL of the mempool.space API for testnet3.
+pub const TESTNET_URL: &str = "https://mempool.space/testnet/api";
+/// URL of the mempool.space API for testnet4.
+pub const TESTNET4_URL: &str = "https://mempool.space/testnet4/api";
+/// URL of the mempool.space API for signet.
+pub const SIGNET_URL: &str = "https://mempool.space/signet/api";
+
+/// Networks that can be identified by their genesis block hash.
+const NETWORKS: [Network; 5] = [
+    Network::Bitcoin,
+    Network::Testnet,
+    Network::Testnet4,
+    Network::Signet,
+    Network::Regtest,
+];
+
+/// Returns the mempool.space API URL for the given `network`.
+///
+/// Returns `None` for [`Network::Regtest`], which has no public instance.
+pub fn network_url(network: Network) -> Option<&'static str> {
+    match network {
+        Network::Bitcoin => Some(MAINNET_URL),
+        Network::Testnet => Some(TESTNET_URL),
+        Network::Testnet4 => Some(TESTNET4_URL),
+        Network::Signet => Some(SIGNET_URL),
+        _ => None,
+    }
+}
+
+/// Returns the [`Network`] whose genesis block has the given `hash`, if known.
+///
+/// Recognizes mainnet, testnet3, testnet4, signet and regtest. Note that custom signets
+/// share the default signet genesis block.
+pub fn network_from_genesis_hash(hash: &BlockHash) -> Option<Network> {
+    NETWORKS
+        .into_iter()
+        .find(|&network| genesis_block(network).block_hash() == *hash)
+}
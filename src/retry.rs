@@ -0,0 +1,138 @@
+//! [`RetryPolicy`].
+
+use core::fmt::Debug;
+use core::time::Duration;
+
+/// Base backoff in milliseconds.
+const BASE_BACKOFF_MILLIS: u64 = 256;
+/// Default max retries.
+const DEFAULT_MAX_RETRIES: u32 = 6;
+
+/// Trait describing when and how long to wait before retrying a failed request.
+pub trait RetryPolicy: Debug + Send + Sync {
+    /// Returns the delay to wait before making the next attempt, or `None` if the request
+    /// should not be retried.
+    ///
+    /// `attempt` is the number of retries made so far, starting at `0`. `status` is the
+    /// HTTP status code of the failed response, or `None` if no response was received.
+    fn next_delay(&self, attempt: u32, status: Option<u16>) -> Option<Duration>;
+}
+
+/// Retries with a delay that doubles after each attempt.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExponentialBackoff {
+    /// The delay before the first retry.
+    pub base: Duration,
+    /// The maximum number of times to retry a failed request.
+    pub max_retries: u32,
+}
+
+impl ExponentialBackoff {
+    /// New with the given `max_retries` and the default base delay.
+    pub fn new(max_retries: u32) -> Self {
+        Self {
+            max_retries,
+            ..Default::default()
+        }
+    }
+}
+
+impl Default for ExponentialBackoff {
+    fn default() -> Self {
+        Self {
+            base: Duration::from_millis(BASE_BACKOFF_MILLIS),
+            max_retries: DEFAULT_MAX_RETRIES,
+        }
+    }
+}
+
+impl RetryPolicy for ExponentialBackoff {
+    fn next_delay(&self, attempt: u32, status: Option<u16>) -> Option<Duration> {
+        if attempt >= self.max_retries || !is_status_retryable(status?) {
+            return None;
+        }
+        Some(self.base.saturating_mul(2u32.saturating_pow(attempt)))
+    }
+}
+
+/// Retries with the same delay between each attempt.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConstantBackoff {
+    /// The delay between attempts.
+    pub delay: Duration,
+    /// The maximum number of times to retry a failed request.
+    pub max_retries: u32,
+}
+
+impl RetryPolicy for ConstantBackoff {
+    fn next_delay(&self, attempt: u32, status: Option<u16>) -> Option<Duration> {
+        if attempt >= self.max_retries || !is_status_retryable(status?) {
+            return None;
+        }
+        Some(self.delay)
+    }
+}
+
+/// Never retries, so that a failed request errors immediately.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct NoRetry;
+
+impl RetryPolicy for NoRetry {
+    fn next_delay(&self, _attempt: u32, _status: Option<u16>) -> Option<Duration> {
+        None
+    }
+}
+
+/// Whether the response status indicates a failure which can be retried.
+///
+/// Currently includes:
+///
+/// - `429`: TOO_MANY_REQUESTS
+/// - `500`: INTERNAL_SERVER_ERROR
+/// - `503`: SERVICE_UNAVAILABLE
+pub fn is_status_retryable(status: u16) -> bool {
+    [429, 500, 503].contains(&status)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_exponential_backoff() {
+        let policy = ExponentialBackoff::default();
+        let delays: Vec<u64> = (0..)
+            .map_while(|attempt| policy.next_delay(attempt, Some(429)))
+            .map(|delay| delay.as_millis() as u64)
+            .collect();
+        assert_eq!(delays, [256, 512, 1024, 2048, 4096, 8192]);
+
+        for status in [500, 503] {
+            assert_eq!(policy.next_delay(0, Some(status)), Some(Duration::from_millis(256)));
+        }
+        for status in [400, 404, 502] {
+            assert_eq!(policy.next_delay(0, Some(status)), None);
+        }
+        assert_eq!(policy.next_delay(0, None), None);
+        assert_eq!(ExponentialBackoff::new(0).next_delay(0, Some(429)), None);
+    }
+
+    #[test]
+    fn test_constant_backoff() {
+        let policy = ConstantBackoff {
+            delay: Duration::from_secs(1),
+            max_retries: 2,
+        };
+        assert_eq!(policy.next_delay(0, Some(503)), Some(Duration::from_secs(1)));
+        assert_eq!(policy.next_delay(1, Some(503)), Some(Duration::from_secs(1)));
+        assert_eq!(policy.next_delay(2, Some(503)), None);
+        assert_eq!(policy.next_delay(0, Some(404)), None);
+        assert_eq!(policy.next_delay(0, None), None);
+    }
+
+    #[test]
+    fn test_no_retry() {
+        assert_eq!(NoRetry.next_delay(0, Some(429)), None);
+        assert_eq!(NoRetry.next_delay(0, None), None);
+    }
+}
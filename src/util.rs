@@ -0,0 +1,59 @@
+//! [`util`](self).
+
+use bitcoin::block::Header;
+use bitcoin::pow::Target;
+
+/// Number of blocks between difficulty adjustments.
+pub const DIFFICULTY_ADJUSTMENT_INTERVAL: u32 = 2016;
+
+/// Number of blocks between subsidy halvings.
+pub const SUBSIDY_HALVING_INTERVAL: u32 = 210_000;
+
+/// Returns the block subsidy in satoshis at the given `height`.
+///
+/// The subsidy starts at 50 BTC and halves every [`SUBSIDY_HALVING_INTERVAL`] blocks,
+/// rounding down, until it reaches zero.
+pub fn block_subsidy(height: u32) -> u64 {
+    let halvings = height / SUBSIDY_HALVING_INTERVAL;
+    if halvings >= 64 {
+        return 0;
+    }
+    (50 * 100_000_000) >> halvings
+}
+
+/// Returns the number of blocks into the difficulty epoch at the given `height`, and the
+/// fractional progress (from `0.0` to `1.0`) toward the next retarget.
+pub fn epoch_progress(height: u32) -> (u32, f64) {
+    let blocks = height % DIFFICULTY_ADJUSTMENT_INTERVAL;
+    let progress = f64::from(blocks) / f64::from(DIFFICULTY_ADJUSTMENT_INTERVAL);
+
+    (blocks, progress)
+}
+
+/// Returns the difficulty of the block with the given `header`, as reported by
+/// [`BlockSummary::difficulty`](crate::api::BlockSummary::difficulty).
+pub fn header_difficulty(header: &Header) -> f64 {
+    header.target().difficulty_float()
+}
+
+/// Returns the [`Target`] expanded from the compact `bits` of the given `header`.
+pub fn header_target(header: &Header) -> Target {
+    Target::from_compact(header.bits)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_block_subsidy() {
+        assert_eq!(block_subsidy(0), 5_000_000_000);
+        assert_eq!(block_subsidy(209_999), 5_000_000_000);
+        assert_eq!(block_subsidy(210_000), 2_500_000_000);
+        assert_eq!(block_subsidy(419_999), 2_500_000_000);
+        assert_eq!(block_subsidy(420_000), 1_250_000_000);
+        assert_eq!(block_subsidy(840_000), 312_500_000);
+        assert_eq!(block_subsidy(33 * SUBSIDY_HALVING_INTERVAL), 0);
+        assert_eq!(block_subsidy(u32::MAX), 0);
+    }
+}